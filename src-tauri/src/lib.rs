@@ -1,6 +1,135 @@
+use tauri::Manager;
+
+#[tauri::command]
+fn set_fullscreen(window: tauri::Window, fullscreen: bool) -> Result<(), String> {
+  window.set_fullscreen(fullscreen).map_err(|err| err.to_string())
+}
+
+/// Text-to-speech process of the latest announcement.
+struct Speaker(std::sync::Mutex<Option<std::process::Child>>);
+
+/// Speaks `text` with the operating system's text-to-speech, for webviews without the
+/// Web Speech API. `rate` is relative to the normal speaking speed.
+///
+/// A new announcement cuts off the previous one, the same as in the browser.
+#[tauri::command]
+fn speak(state: tauri::State<Speaker>, text: String, rate: f32) -> Result<(), String> {
+  #[cfg(target_os = "macos")]
+  let mut command = {
+    let mut command = std::process::Command::new("say");
+    let words_per_minute = ((175.0 * rate) as u32).to_string();
+    // "--" keeps a text starting with "-" from being read as an option.
+    command.args(["-r", &words_per_minute, "--", &text]);
+    command
+  };
+
+  #[cfg(target_os = "windows")]
+  let mut command = {
+    // The text goes through the environment so it is never parsed as PowerShell.
+    let mut command = std::process::Command::new("powershell");
+    let speech_rate = (((rate - 1.0) * 10.0) as i32).clamp(-10, 10);
+    command
+      .env("TIMER_SPEECH", &text)
+      .env("TIMER_SPEECH_RATE", speech_rate.to_string())
+      .args([
+        "-NoProfile",
+        "-Command",
+        "Add-Type -AssemblyName System.Speech; \
+         $voice = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+         $voice.Rate = [int]$env:TIMER_SPEECH_RATE; \
+         $voice.Speak($env:TIMER_SPEECH)",
+      ]);
+    command
+  };
+
+  #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+  let mut command = {
+    let mut command = std::process::Command::new("espeak");
+    let words_per_minute = ((175.0 * rate) as u32).to_string();
+    command.args(["-s", &words_per_minute, "--", &text]);
+    command
+  };
+
+  let mut speaking = state.0.lock().map_err(|err| err.to_string())?;
+  stop(&mut speaking);
+  command.stdin(std::process::Stdio::null());
+  *speaking = Some(command.spawn().map_err(|err| err.to_string())?);
+  Ok(())
+}
+
+/// Helper process that keeps the display awake for as long as it runs.
+struct SleepInhibitor(std::sync::Mutex<Option<std::process::Child>>);
+
+fn inhibitor_command() -> std::process::Command {
+  #[cfg(target_os = "macos")]
+  let command = {
+    // `-w` ends the assertion with the app, even if the app is killed.
+    let mut command = std::process::Command::new("caffeinate");
+    command.args(["-d", "-w", &std::process::id().to_string()]);
+    command
+  };
+
+  #[cfg(target_os = "windows")]
+  let command = {
+    // The execution state belongs to the calling thread and is cleared when the process ends,
+    // which it does once the app is gone.
+    let mut command = std::process::Command::new("powershell");
+    command
+      .env("TIMER_PARENT", std::process::id().to_string())
+      .args([
+        "-NoProfile",
+        "-Command",
+        "$code = '[DllImport(\"kernel32.dll\")] public static extern uint SetThreadExecutionState(uint esFlags);'; \
+         $power = Add-Type -MemberDefinition $code -Name Power -Namespace Timer -PassThru; \
+         $power::SetThreadExecutionState([uint32]'0x80000003') | Out-Null; \
+         while (Get-Process -Id $env:TIMER_PARENT -ErrorAction SilentlyContinue) { Start-Sleep -Seconds 10 }",
+      ]);
+    command
+  };
+
+  #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+  let command = {
+    let mut command = std::process::Command::new("systemd-inhibit");
+    command.args([
+      "--what=idle:sleep",
+      "--who=Boardgame timer",
+      "--why=A game is running",
+      "tail",
+    ]);
+    // Holds the lock until the app exits, even if the app is killed.
+    command
+      .arg(format!("--pid={}", std::process::id()))
+      .args(["-f", "/dev/null"]);
+    command
+  };
+
+  command
+}
+
+/// Ends a helper process, if any, and reaps it.
+fn stop(child: &mut Option<std::process::Child>) {
+  if let Some(mut child) = child.take() {
+    // Killing a helper that already finished fails harmlessly; waiting reaps it.
+    let _ = child.kill();
+    let _ = child.wait();
+  }
+}
+
+#[tauri::command]
+fn inhibit_sleep(state: tauri::State<SleepInhibitor>, inhibit: bool) -> Result<(), String> {
+  let mut running = state.0.lock().map_err(|err| err.to_string())?;
+  stop(&mut running);
+  if inhibit {
+    *running = Some(inhibitor_command().spawn().map_err(|err| err.to_string())?);
+  }
+  Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .manage(Speaker(std::sync::Mutex::new(None)))
+    .manage(SleepInhibitor(std::sync::Mutex::new(None)))
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -11,6 +140,18 @@ pub fn run() {
       }
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .invoke_handler(tauri::generate_handler![set_fullscreen, speak, inhibit_sleep])
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      // Helper processes are not stopped when their handles are dropped.
+      if let tauri::RunEvent::Exit = event {
+        if let Ok(mut running) = app.state::<SleepInhibitor>().0.lock() {
+          stop(&mut running);
+        }
+        if let Ok(mut speaking) = app.state::<Speaker>().0.lock() {
+          stop(&mut speaking);
+        }
+      }
+    });
 }
@@ -16,85 +16,82 @@ fn Windows() -> impl IntoView {
     let bottom_panel_ref = NodeRef::<html::Div>::new();
     let (panel_size, set_panel_size) = signal((0, 0, 10000, 10000)); // Track size
     let config = RwSignal::new(Config::new());
+    // Table mode hides everything but the clocks and the game controls.
+    let presentation = RwSignal::new(false);
 
-    // Set up ResizeObserver
+    // Set up ResizeObserver, disconnected again whenever the panel goes away.
     Effect::new(move || {
         if let Some(panel) = bottom_panel_ref.get() {
             let element: &web_sys::HtmlDivElement = panel.as_ref();
+            logging::log!(
+                "Element bounds: x{}, y{}",
+                element.get_bounding_client_rect().x(),
+                element.get_bounding_client_rect().y()
+            );
 
-            if let Some(panel) = bottom_panel_ref.get() {
-                logging::log!(
-                    "Element bounds: x{}, y{}",
-                    panel.get_bounding_client_rect().x(),
-                    panel.get_bounding_client_rect().y()
-                );
-
-                let callback =
-                    wasm_bindgen::closure::Closure::wrap(Box::new(move |entries: js_sys::Array| {
-                        if entries
-                            .get(0)
-                            .dyn_into::<web_sys::ResizeObserverEntry>()
-                            .is_ok()
-                        {
-                            let panel_rect = panel.get_bounding_client_rect();
-                            set_panel_size.set((
-                                panel_rect.x() as i32,
-                                panel_rect.y() as i32,
-                                panel_rect.width() as i32,
-                                panel_rect.height() as i32,
-                            ));
-                            logging::log!(
-                                "Panel resized to: {}x{}",
-                                panel_rect.width() as i32,
-                                panel_rect.height() as i32
-                            );
-                        }
-                    })
-                        as Box<dyn FnMut(js_sys::Array)>);
+            let callback = {
+                let panel = panel.clone();
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |entries: js_sys::Array| {
+                    if entries
+                        .get(0)
+                        .dyn_into::<web_sys::ResizeObserverEntry>()
+                        .is_ok()
+                    {
+                        let panel_rect = panel.get_bounding_client_rect();
+                        set_panel_size.set((
+                            panel_rect.x() as i32,
+                            panel_rect.y() as i32,
+                            panel_rect.width() as i32,
+                            panel_rect.height() as i32,
+                        ));
+                        logging::log!(
+                            "Panel resized to: {}x{}",
+                            panel_rect.width() as i32,
+                            panel_rect.height() as i32
+                        );
+                    }
+                })
+                    as Box<dyn FnMut(js_sys::Array)>)
+            };
 
-                let observer =
-                    web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap();
-                observer.observe(element);
+            let observer = web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap();
+            observer.observe(element);
 
-                callback.forget();
-            }
+            // The callback has to outlive the observer, so both are dropped together.
+            let resize = StoredValue::new_local((observer, callback));
+            on_cleanup(move || {
+                resize.with_value(|(observer, _)| observer.disconnect());
+                logging::log!("Panel observer disconnected");
+            });
         }
     });
 
     let (show, set_show) = signal(true);
     view! {
-        <div class="resizable-horizontal-container">
+        <div
+            class="resizable-horizontal-container"
+            style=move || config.with(|c| timer::css_variables(c.theme, c.font_scale))
+        >
             {move || {
                 view! {
                     <div
                         class="top-panel"
-                        style:display=move || if show.get() { "block" } else { "none" }
+                        style:display=move || {
+                            if show.get() && !presentation.get() { "block" } else { "none" }
+                        }
                     >
                         <Configuration config_signal=config />
                     </div>
                 }
             }}
             <div
-                style:height="8px"
-                style:background-color="gray"
-                style:display="flex"
-                style:justify-content="center"
-                style:align-items="center"
-                style:position="relative"
+                class="panel-divider"
+                style:display=move || if presentation.get() { "none" } else { "flex" }
             >
                 <button
-                    style:cursor="pointer"
-                    style:width="20px"
-                    style:height="20px"
-                    style:position="absolute"
-                    style:background-color="#666"
-                    style:border="1px solid #999"
-                    style:border-radius="3px"
-                    style:display="flex"
-                    style:align-items="center"
-                    style:justify-content="center"
-                    style:color="white"
-                    style:font-size="25px"
+                    class="panel-divider-button"
+                    aria-label=move || config.with(|c| c.locale.text("Settings"))
+                    aria-expanded=move || show.get().to_string()
                     on:click=move |_| {
                         set_show.set(!show.get());
                         if show.get() {
@@ -113,7 +110,7 @@ fn Windows() -> impl IntoView {
                 class="bottom-panel"
                 style:height="100%"
             >
-                <App panel_size config />
+                <App panel_size config presentation />
             </div>
         </div>
     }
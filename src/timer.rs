@@ -1,11 +1,45 @@
+mod buttons;
 mod config;
+mod duration;
+mod fullscreen;
+mod gesture;
+mod history;
+mod i18n;
+mod keyboard;
+mod layout;
+mod native;
+mod pause;
+mod phone;
+mod placement;
+mod score;
+mod sound;
+mod speech;
+mod storage;
+mod summary;
+mod team;
+mod theme;
+mod wake;
 
 pub use config::{Config, Configuration};
+pub use theme::css_variables;
 
-use leptos::{logging, prelude::*};
+use buttons::{seat_for_key, watch_gamepads};
+use config::MAX_SNAP_GRID;
+use fullscreen::{clock_size, set_fullscreen};
+use gesture::{Gesture, GestureUpdate};
+use history::{AdjustTime, Event, GameLog, LogEntry, Turn, TurnEditor};
+use keyboard::{Action, KeyHelp, is_typing};
+use layout::{SavedLayouts, seat_positions};
+use leptos::{ev, html, logging, prelude::*};
+use pause::{Break, PauseBanner, PauseReason};
+use phone::PhoneClock;
+use placement::{Rect, overlaps, push_out, snap_to_grid};
+use sound::Sound;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::time::Duration;
+use summary::Summary;
+use team::turn_order;
 use wasm_bindgen::JsCast;
 use web_time::Instant;
 
@@ -13,15 +47,23 @@ use web_time::Instant;
 struct Player {
     id: usize,
     name: RwSignal<String>,
-    time: RwSignal<Vec<f32>>,
+    time: RwSignal<Vec<Turn>>,
     position: ArcRwSignal<(i32, i32)>,
     rotation: RwSignal<f32>,
+    team: Option<usize>,
     clock: Clock,
+    /// Bonus (or, if negative, penalty) added to the player's time bank by hand.
+    bank_bonus: RwSignal<f32>,
+    /// Score entered for each round.
+    scores: RwSignal<Vec<Option<i32>>>,
+    /// Score entered at the end of the game, replacing the sum of the round scores.
+    final_score: RwSignal<Option<i32>>,
 }
 impl Player {
     fn new(
         id: usize,
         name: String,
+        team: Option<usize>,
         global_timer: ReadSignal<f32>,
         start_timer: ReadSignal<f32>,
     ) -> Self {
@@ -31,15 +73,28 @@ impl Player {
             time: RwSignal::new(Vec::new()),
             position: ArcRwSignal::new(((id as i32 + 1) * 120, 250)),
             rotation: RwSignal::new(0.0),
+            team,
             clock: Clock::new(global_timer, start_timer),
+            bank_bonus: RwSignal::new(0.0),
+            scores: RwSignal::new(Vec::new()),
+            final_score: RwSignal::new(None),
         }
     }
+
+    /// The final score if one was entered, otherwise the sum of the round scores.
+    fn score(&self) -> Option<i32> {
+        self.final_score.get().or_else(|| {
+            self.scores
+                .with(|scores| scores.iter().flatten().copied().reduce(|a, b| a + b))
+        })
+    }
 }
 
 #[component]
 pub fn App(
     panel_size: ReadSignal<(i32, i32, i32, i32)>,
     config: RwSignal<Config>,
+    presentation: RwSignal<bool>,
 ) -> impl IntoView {
     let background_timer = RwSignal::new(0.0);
     let active_game = RwSignal::new(false);
@@ -51,7 +106,9 @@ pub fn App(
 
     let now = Instant::now();
     let update = move || background_timer.set(now.elapsed().as_secs_f32());
-    set_interval(update, Duration::from_millis(100));
+    if let Ok(handle) = set_interval_with_handle(update, Duration::from_millis(100)) {
+        on_cleanup(move || handle.clear());
+    }
 
     Effect::new(move || {
         if active_game.get() {
@@ -59,48 +116,347 @@ pub fn App(
         }
     });
 
-    let pause_unpause = move |_| {
+    // The game ends once every player has had the configured rounds or time runs out.
+    let players: RwSignal<Vec<Player>> = RwSignal::new(Vec::new());
+    let completed_rounds = Memo::new(move |_| {
+        players.with(|players| {
+            players
+                .iter()
+                .map(|player| player.time.with(|turns| turns.len()))
+                .min()
+                .unwrap_or(0)
+        })
+    });
+    let out_of_time = move || {
+        let limit = config.with(|c| c.time_limit);
+        limit > 0 && global_timer.get() >= limit as f32
+    };
+    let finished = Memo::new(move |_| {
+        let rounds = config.with(|c| c.rounds) as usize;
+        (rounds > 0 && completed_rounds.get() >= rounds) || out_of_time()
+    });
+    let show_summary = RwSignal::new(false);
+
+    let log: RwSignal<Vec<LogEntry>> = RwSignal::new(Vec::new());
+    // The pause in progress, logged with its reason once the game resumes.
+    let pause: RwSignal<Option<Break>> = RwSignal::new(None);
+    let log_pause = move || {
+        if let Some(ended) = pause.get_untracked() {
+            let seconds = background_timer.get_untracked() - ended.since;
+            // Ignore a stray double tap on the pause button.
+            if seconds >= 1.0 {
+                log.update(|log| {
+                    log.push(LogEntry {
+                        at: elapsed_time.get_untracked(),
+                        event: Event::Paused {
+                            reason: ended.reason,
+                            seconds,
+                        },
+                    })
+                });
+            }
+        }
+    };
+    // Wall-clock time of the last pointer, key or gamepad press.
+    let last_input = RwSignal::new(0.0);
+
+    let pause_unpause = move || {
+        if finished.get_untracked() && !active_game.get_untracked() {
+            return;
+        }
         active_game.set(!active_game.get());
         if active_game.get() {
             unpause_time.set(background_timer.get());
+            last_input.set(background_timer.get());
+            log_pause();
+            pause.set(None);
         } else {
             elapsed_time.set(global_timer.get());
+            pause.set(Some(Break {
+                reason: PauseReason::Pause,
+                since: background_timer.get(),
+            }));
+        }
+    };
+    // Pauses a running game, or gives the current pause a new reason.
+    let pause_for = move |reason: PauseReason| {
+        if active_game.get_untracked() {
+            pause_unpause();
+        }
+        if pause.with_untracked(|pause| pause.is_some_and(|pause| pause.reason != reason)) {
+            log_pause();
+            pause.set(Some(Break {
+                reason,
+                since: background_timer.get_untracked(),
+            }));
         }
     };
 
-    let players = RwSignal::new(Vec::new());
+    let touch = move || last_input.set(background_timer.get_untracked());
+    let pointerdown = window_event_listener(ev::pointerdown, move |_| touch());
+    let keypress = window_event_listener(ev::keydown, move |_| touch());
+    on_cleanup(move || {
+        pointerdown.remove();
+        keypress.remove();
+    });
+    Effect::new(move |_| {
+        let idle = config.with(|c| c.idle_pause);
+        let idle_for = background_timer.get() - last_input.get();
+        if idle > 0 && active_game.get() && idle_for >= idle as f32 {
+            logging::log!("pausing after {} s without interaction", idle_for);
+            pause_for(PauseReason::Idle);
+            // Nobody was playing since the last interaction, so that time counts as part of
+            // the break rather than the running turn.
+            let idle_for = idle_for.min(elapsed_time.get_untracked() - start_timer.get_untracked());
+            if idle_for > 0.0 {
+                elapsed_time.update(|elapsed| *elapsed -= idle_for);
+                set_global_timer.set(elapsed_time.get_untracked());
+                pause.update(|pause| {
+                    if let Some(pause) = pause {
+                        pause.since -= idle_for;
+                    }
+                });
+            }
+        }
+    });
+
+    let order = Memo::new(move |_| turn_order(&config.get()));
+
+    // Remaining bank per seat; members of a team with a shared bank draw from the same total.
+    let banks = Memo::new(move |_| {
+        let config = config.get();
+        let players = players.get();
+        if config.time_bank == 0 {
+            return vec![None; players.len()];
+        }
+        let used: Vec<f32> = players
+            .iter()
+            .map(|p| {
+                let running = if p.clock.active.get() {
+                    p.clock.timer.get()
+                } else {
+                    0.0
+                };
+                p.time.get().iter().map(|turn| turn.seconds).sum::<f32>() + running
+            })
+            .collect();
+        players
+            .iter()
+            .map(|p| {
+                // A shared bank grows with the average allowance of the team's members.
+                let allowance = |p: &Player| match p.team {
+                    Some(team) if config.teams.get(team).is_some_and(|t| t.shared_bank) => {
+                        let members: Vec<f32> = players
+                            .iter()
+                            .filter(|other| other.team == Some(team))
+                            .map(|other| config.handicap(other.id))
+                            .collect();
+                        members.iter().sum::<f32>() / members.len() as f32
+                    }
+                    _ => config.handicap(p.id),
+                };
+                let (spent, bonus): (f32, f32) = match p.team {
+                    Some(team) if config.teams.get(team).is_some_and(|t| t.shared_bank) => players
+                        .iter()
+                        .filter(|other| other.team == Some(team))
+                        .map(|other| (used[other.id], other.bank_bonus.get()))
+                        .fold((0.0, 0.0), |(s, b), (spent, bonus)| (s + spent, b + bonus)),
+                    _ => (used[p.id], p.bank_bonus.get()),
+                };
+                Some(config.time_bank as f32 * allowance(p) + bonus - spent)
+            })
+            .collect()
+    });
 
     let reset_players = move || {
-        let count = config.get().nplayers;
+        let config = config.get();
         // Create a completely new vector of players with fresh signals.
-        let new_players = (0..count)
-            .map(|i| Player::new(i, config.get().names[i].clone(), global_timer, start_timer))
+        let new_players = (0..config.nplayers)
+            .map(|i| {
+                Player::new(
+                    i,
+                    config.player_name(i),
+                    config.team_of(i),
+                    global_timer,
+                    start_timer,
+                )
+            })
             .collect();
         players.set(new_players);
-        logging::log!(
-            "{} players, game {}",
-            config.get().nplayers,
-            config.get().game_counter
-        );
+        logging::log!("{} players, game {}", config.nplayers, config.game_counter);
     };
     let reset_game = move || {
         active_game.set(false);
         set_global_timer.set(0.0);
         set_start_timer.set(0.0);
         reset_players();
+        log.set(Vec::new());
+        pause.set(None);
+        show_summary.set(false);
+        let first = config.with_untracked(|c| c.first_player);
+        let order = order.get_untracked();
+        set_active_player.set(if order.contains(&first) {
+            first
+        } else {
+            order.first().copied().unwrap_or(0)
+        });
     };
     Effect::new(move |_| {
         logging::log!("resetting game from effect");
         reset_game();
     });
-    let go_back = move |_| {
-        let next: usize = match active_player.get() == 0 {
-            true => config.get().nplayers - 1,
-            false => active_player.get() - 1,
+    // Only the active seat's clock runs.
+    Effect::new(move |_| {
+        let active = active_player.get();
+        for player in players.get() {
+            player.clock.active.set(player.id == active);
+        }
+        logging::log!("active_player {}", active);
+    });
+
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+
+    // Text for the screen-reader live region.
+    let announcement = RwSignal::new(String::new());
+    Effect::new(move |previous: Option<bool>| {
+        let running = active_game.get();
+        if previous.is_some_and(|previous| previous != running) {
+            announcement.set(t(if running { "Clock running" } else { "Paused" }).to_string());
+        }
+        running
+    });
+
+    // Sound alerts for turn changes, warning thresholds and running out of bank time.
+    Effect::new(move |previous: Option<usize>| {
+        let active = active_player.get();
+        if previous.is_some_and(|previous| previous != active) {
+            let name = players.with_untracked(|players| players.get(active).map(|p| p.name.get()));
+            config.with_untracked(|c| {
+                c.alerts.play(Sound::TurnChange);
+                if let Some(name) = name {
+                    c.speech
+                        .announce(c.locale, &c.speech.turn_template, &name, 0.0);
+                    announcement.set(c.locale.text("{name}'s turn").replace("{name}", &name));
+                }
+            });
+        }
+        active
+    });
+    Effect::new(move |previous: Option<(usize, f32)>| {
+        let active = active_player.get();
+        let Some(player) = players.with(|players| players.get(active).cloned()) else {
+            return (active, 0.0);
         };
+        let bank = banks.get().get(active).copied().flatten();
+        let now = bank.unwrap_or_else(|| player.clock.timer.get());
+        // Only compare against the same turn, never across a change of player.
+        if let Some((_, before)) = previous.filter(|&(seat, _)| seat == active) {
+            let crossed = |at: f32| match bank {
+                Some(_) => before > at && now <= at,
+                None => before < at && now >= at,
+            };
+            let name = player.name.get_untracked();
+            config.with_untracked(|c| {
+                if bank.is_some() && crossed(0.0) {
+                    c.alerts.play(Sound::Expiry);
+                    c.speech
+                        .announce(c.locale, &c.speech.expiry_template, &name, 0.0);
+                    announcement.set(
+                        c.locale
+                            .text("{name} is out of time")
+                            .replace("{name}", &name),
+                    );
+                } else if let Some(&at) = c.alerts.warning_at.iter().find(|&&at| crossed(at as f32))
+                {
+                    c.alerts.play(Sound::Warning);
+                    c.speech
+                        .announce(c.locale, &c.speech.warning_template, &name, at as f32);
+                }
+            });
+        }
+        (active, now)
+    });
+    // Screen readers pick their pronunciation from the document language.
+    Effect::new(move |_| {
+        let locale = config.with(|c| c.locale);
+        if let Some(root) = document().document_element() {
+            let _ = root.set_attribute("lang", locale.key());
+        }
+    });
+    // Keep the screen on while the clocks are running.
+    Effect::new(move |_| {
+        if active_game.get() && config.with(|c| c.keep_awake) {
+            wake::acquire();
+        } else {
+            wake::release();
+        }
+    });
+    let visibility_change =
+        window_event_listener_untyped("visibilitychange", move |_| wake::reacquire_if_visible());
+    on_cleanup(move || {
+        visibility_change.remove();
+        wake::release();
+    });
+
+    // Clocks grow to fill the table in presentation mode.
+    let clock = Memo::new(move |_| {
+        if presentation.get() {
+            clock_size(players.get().len(), panel_size.get())
+        } else {
+            100
+        }
+    });
+    let toggle_presentation = move || {
+        presentation.update(|on| *on = !*on);
+        set_fullscreen(presentation.get());
+    };
+    // Leaving fullscreen from the browser (e.g. with Escape) also ends presentation mode.
+    let fullscreen_change = window_event_listener_untyped("fullscreenchange", move |_| {
+        if document().fullscreen_element().is_none() && presentation.get_untracked() {
+            presentation.set(false);
+        }
+    });
+    on_cleanup(move || fullscreen_change.remove());
+
+    // Seat the panels according to the configured layout, again whenever the panel resizes.
+    let saved_seating = RwSignal::new(SavedLayouts::load());
+    Effect::new(move |_| {
+        let players = players.get();
+        let config = config.get();
+        let panel = panel_size.get();
+        let seats =
+            seat_positions(config.layout, players.len(), panel, clock.get()).or_else(|| {
+                saved_seating
+                    .get()
+                    .restore(&config.table, players.len(), panel)
+            });
+        if let Some(seats) = seats {
+            for (player, (position, rotation)) in players.iter().zip(seats) {
+                player.position.set(position);
+                player.rotation.set(rotation);
+            }
+        }
+    });
+    // Position of the active seat within the current turn order.
+    let turn_position = move || {
+        order
+            .get()
+            .iter()
+            .position(|&seat| seat == active_player.get())
+            .unwrap_or(0)
+    };
+    let go_back = move || {
+        let order = order.get();
+        if order.is_empty() {
+            return;
+        }
+        let next = order[(turn_position() + order.len() - 1) % order.len()];
+        if let Some(player) = players.get().get(active_player.get()) {
+            player.clock.offset.set(0.0);
+        }
         let next_player = &mut players.get()[next];
         let mut next_player_time = next_player.time.get();
-        if next_player_time.len() > 0 {
+        if !next_player_time.is_empty() {
             next_player_time.pop();
             next_player.time.set(next_player_time);
         }
@@ -108,15 +464,158 @@ pub fn App(
         set_start_timer.set(global_timer.get());
     };
 
-    let player_toggle = move || {
-        let next: usize = (active_player.get() + 1) % config.get().nplayers;
-        logging::log!("calling increment on {}", next);
+    // Records the active player's turn and hands the clock to `next`.
+    let pass_turn = move |next: usize| {
+        if let Some(player) = players.get().get(active_player.get()) {
+            player
+                .time
+                .update(|timer| timer.push(Turn::new(player.clock.timer.get().max(0.0))));
+            logging::log!(
+                "pushing time on player {}: t{}",
+                player.id,
+                player.clock.timer.get()
+            );
+            player.clock.offset.set(0.0);
+        }
         set_active_player.set(next);
         set_start_timer.set(global_timer.get());
     };
+    let player_toggle = move || {
+        let order = order.get();
+        if order.is_empty() {
+            return;
+        }
+        let next = order[(turn_position() + 1) % order.len()];
+        logging::log!("calling increment on {}", next);
+        pass_turn(next);
+    };
+    let jump_to = move |seat: usize| {
+        if seat < players.get().len() && seat != active_player.get() {
+            logging::log!("jumping to player {}", seat);
+            pass_turn(seat);
+        }
+    };
+
+    Effect::new(move |was_finished: Option<bool>| {
+        let finished = finished.get();
+        if finished && was_finished == Some(false) {
+            logging::log!(
+                "game over after {} rounds",
+                completed_rounds.get_untracked()
+            );
+            if active_game.get_untracked() {
+                pause_unpause();
+            }
+            pause.set(None);
+            // A game stopped by the time limit ends in the middle of a turn.
+            if untrack(out_of_time) {
+                if let Some(player) = players.get_untracked().get(active_player.get_untracked()) {
+                    let seconds = player.clock.timer.get_untracked();
+                    player.time.update(|turns| turns.push(Turn::new(seconds)));
+                    player.clock.offset.set(0.0);
+                }
+                set_start_timer.set(global_timer.get_untracked());
+            }
+            show_summary.set(true);
+        }
+        finished
+    });
+    let round_label = move || {
+        let rounds = config.with(|c| c.rounds) as usize;
+        let round = completed_rounds.get() + 1;
+        let mut label = if rounds > 0 {
+            t("Round {n} of {total}")
+                .replace("{n}", &round.min(rounds).to_string())
+                .replace("{total}", &rounds.to_string())
+        } else {
+            t("Round {n}").replace("{n}", &round.to_string())
+        };
+        let limit = config.with(|c| c.time_limit);
+        if limit > 0 {
+            let left = (limit as f32 - global_timer.get()).max(0.0);
+            label.push_str(" · ");
+            label.push_str(
+                &t("{time} left").replace("{time}", &config.with(|c| c.format_time(left))),
+            );
+        }
+        label
+    };
+
+    // A seat's own button ends its turn, the same as clicking its clock.
+    let seat_pressed = move |seat: usize| {
+        if seat == active_player.get() {
+            player_toggle();
+        }
+    };
+    watch_gamepads(move |button| {
+        touch();
+        let seat_buttons = config.get().seat_buttons;
+        if let Some(seat) = seat_buttons
+            .iter()
+            .position(|b| b.as_ref() == Some(&button))
+        {
+            seat_pressed(seat);
+        }
+    });
+
+    let remember_seating = move || {
+        let seats: Vec<_> = players
+            .get()
+            .iter()
+            .map(|player| (player.position.get(), player.rotation.get()))
+            .collect();
+        logging::log!("remembering seating for {} players", seats.len());
+        saved_seating.update(|saved| saved.remember(&config.get().table, &seats, panel_size.get()));
+    };
+
+    let show_help = RwSignal::new(false);
+    let keydown = window_event_listener(ev::keydown, move |ev| {
+        if ev.repeat() || ev.default_prevented() || is_typing(&ev) {
+            return;
+        }
+        if let Some(seat) = seat_for_key(&config.get().seat_buttons, &ev.key()) {
+            ev.prevent_default();
+            seat_pressed(seat);
+            return;
+        }
+        if ev.key() == "Escape" {
+            show_help.set(false);
+            return;
+        }
+        let Some(action) = config.get().keys.action_for(&ev.key()) else {
+            return;
+        };
+        ev.prevent_default();
+        match action {
+            Action::EndTurn => player_toggle(),
+            Action::Pause => pause_unpause(),
+            Action::Back => go_back(),
+            Action::Help => show_help.update(|show| *show = !*show),
+            Action::Fullscreen => toggle_presentation(),
+            Action::JumpTo(seat) => jump_to(seat),
+        }
+    });
+    on_cleanup(move || keydown.remove());
+
+    let controls_ref = NodeRef::<html::Div>::new();
 
     view! {
-        <div class="global-content">
+        <div
+            class="global-content"
+            class:global-content-top=move || config.get().single_device
+            node_ref=controls_ref
+        >
+            <Show
+                when=move || finished.get()
+                fallback=move || view! { <div class="round-counter">{round_label}</div> }
+            >
+                <button class="round-counter round-counter-over" on:click=move |_| show_summary.set(true)>
+                    {move || t("Game over")}
+                </button>
+            </Show>
+            <Show when=move || !finished.get()>
+                <PauseBanner pause background_timer config change_reason=pause_for resume=pause_unpause />
+            </Show>
             <div class="control-buttons-container">
             <button class=move || {
                     if active_game.get() {
@@ -125,32 +624,81 @@ pub fn App(
                         "control-button control-button-start"
                     }
                 }
-                on:click=pause_unpause>
+                aria-label=move || t(if active_game.get() { "Pause" } else { "Start" })
+                on:click=move |_| pause_unpause()>
+                </button>
+                <button
+                    class="control-button control-button-back"
+                    title=move || t("Back to previous player")
+                    aria-label=move || t("Back to previous player")
+                    on:click=move |_| go_back()
+                >
                 </button>
-                <button class="control-button control-button-back" on:click=go_back>
+                <button
+                    class="control-button control-button-reset"
+                    title=move || t("Reset game")
+                    aria-label=move || t("Reset game")
+                    on:click=move |_| reset_game()
+                >
                 </button>
-                <button class="control-button control-button-reset" on:click=move |_| {
-                    reset_game();
-                }>
+                <button
+                    class="control-button control-button-seating"
+                    title=move || t("Remember seating")
+                    aria-label=move || t("Remember seating")
+                    on:click=move |_| remember_seating()
+                >
+                </button>
+                <button
+                    class="control-button control-button-fullscreen"
+                    title=move || t("Table mode")
+                    aria-label=move || t("Table mode")
+                    aria-pressed=move || presentation.get().to_string()
+                    on:click=move |_| toggle_presentation()
+                >
                 </button>
             </div>
-            <TimeTable players=players />
+            <Show when=move || !presentation.get() && !config.get().single_device>
+                <TimeTable players config log global_timer />
+                <AdjustTime players config log global_timer active_player />
+            </Show>
+        </div>
+        <KeyHelp
+            bindings=Signal::derive(move || config.get().keys)
+            locale=Signal::derive(move || config.get().locale)
+            show=show_help
+        />
+        <Summary players config log show=show_summary new_game=reset_game />
+        <div class="visually-hidden" role="status" aria-live="polite">
+            {move || announcement.get()}
         </div>
-        <For each=move || players.get()
-            key=move |state| state.name.clone()
-            let:player
+        <Show
+            when=move || config.get().single_device
+            fallback=move || {
+                view! {
+                    <For each=move || players.get()
+                        key=move |state| state.name
+                        let:player
+                    >
+                        <Player player player_toggle panel_size config banks players controls_ref clock />
+                    </For>
+                }
+            }
         >
-            <Player player active_player player_toggle panel_size />
-        </For>
+            <PhoneClock players active_player banks panel_size config player_toggle go_back />
+        </Show>
     }
 }
 
 #[component]
 fn Player(
     player: Player,
-    active_player: ReadSignal<usize>,
-    mut player_toggle: impl FnMut() + 'static,
+    player_toggle: impl FnMut() + 'static,
     panel_size: ReadSignal<(i32, i32, i32, i32)>,
+    config: RwSignal<Config>,
+    banks: Memo<Vec<Option<f32>>>,
+    players: RwSignal<Vec<Player>>,
+    controls_ref: NodeRef<html::Div>,
+    clock: Memo<i32>,
 ) -> impl IntoView {
     let current_panel_size = Rc::new(RefCell::new((0, 0, 0, 0)));
 
@@ -160,113 +708,222 @@ fn Player(
         Effect::new(move || {
             let new_size = panel_size.get();
             *current_panel_size.borrow_mut() = new_size;
-            let (pos_x, pos_y) = player_a1.position.get();
-            let (x, y, panel_width, panel_height) = *current_panel_size.borrow();
-            let new_x = (pos_x).max(x + 20).min(x + panel_width - 20);
-            let new_y = (pos_y).max(y + 20).min(y + panel_height - 20);
-            if pos_x != new_x || pos_y != new_y {
-                player_a1.position.set((new_x, new_y));
+            let position = player_a1.position.get();
+            let clamped = clamp_to_panel(position, new_size);
+            if position != clamped {
+                player_a1.position.set(clamped);
             }
         });
     }
 
-    let player1 = player.clone();
-    let handle_mouse_down = {
-        let is_dragging = true;
-        let player2 = player1.clone();
-        move |ev: web_sys::MouseEvent| {
-            logging::log!("handle_mouse_down for player {}", player2.name.get());
-
-            let p_dragging = Rc::new(RefCell::new(is_dragging));
-            let (px, py) = player2.position.get();
-            let offset_x = ev.client_x() - px;
-            let offset_y = ev.client_y() - py;
-
-            let window = web_sys::window().unwrap();
-            let document = window.document().unwrap();
+    let gesture = Rc::new(RefCell::new(Gesture::new()));
+    let on_pointer_down = {
+        let gesture = Rc::clone(&gesture);
+        let player = player.clone();
+        move |ev: web_sys::PointerEvent| {
+            let on_handle = ev
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .and_then(|element| element.closest(".usertime-name-tag").ok().flatten())
+                .is_some();
+            let mut gesture = gesture.borrow_mut();
+            // Pointers elsewhere on the panel stay ordinary clicks unless they join a gesture.
+            if !on_handle && !gesture.is_tracking() {
+                return;
+            }
+            let drag_from = on_handle.then(|| player.position.get_untracked());
+            let point = (ev.client_x(), ev.client_y());
+            if gesture.down(
+                ev.pointer_id(),
+                point,
+                drag_from,
+                player.rotation.get_untracked(),
+            ) {
+                logging::log!("pointer down {} on player {}", ev.pointer_id(), player.id);
+                ev.prevent_default();
+                if let Some(panel) = ev
+                    .current_target()
+                    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                {
+                    let _ = panel.set_pointer_capture(ev.pointer_id());
+                }
+            }
+        }
+    };
+    // Moves the panel towards `(x, y)`, snapping, clamping and avoiding overlaps as configured.
+    let container_ref = NodeRef::<html::Div>::new();
+    let move_to = {
+        let player = player.clone();
+        Rc::new(move |(x, y): (i32, i32)| {
+            let config = config.get_untracked();
+            let panel = *current_panel_size.borrow();
+            let mut target = clamp_to_panel(snap_to_grid((x, y), config.snap_grid), panel);
+            if config.avoid_overlap {
+                let Some(element) = container_ref.get_untracked() else {
+                    return;
+                };
+                let size = (element.offset_width(), element.offset_height());
+                let mut obstacles: Vec<Rect> = players
+                    .get_untracked()
+                    .iter()
+                    .filter(|other| other.id != player.id)
+                    .map(|other| {
+                        let (ox, oy) = other.position.get_untracked();
+                        (ox, oy, size.0, size.1)
+                    })
+                    .collect();
+                if let Some(controls) = controls_ref.get_untracked() {
+                    let rect = controls.get_bounding_client_rect();
+                    obstacles.push((
+                        rect.x() as i32,
+                        rect.y() as i32,
+                        rect.width() as i32,
+                        rect.height() as i32,
+                    ));
+                }
+                // Stay put rather than end up on top of another panel.
+                match push_out((target.0, target.1, size.0, size.1), &obstacles) {
+                    Some(free) => target = clamp_to_panel(free, panel),
+                    None => return,
+                }
+                let rect = (target.0, target.1, size.0, size.1);
+                if obstacles.iter().any(|&obstacle| overlaps(rect, obstacle)) {
+                    return;
+                }
+            }
+            player.position.set(target);
+        })
+    };
+    let on_pointer_move = {
+        let gesture = Rc::clone(&gesture);
+        let player = player.clone();
+        let move_to = Rc::clone(&move_to);
+        move |ev: web_sys::PointerEvent| {
+            let point = (ev.client_x(), ev.client_y());
+            match gesture.borrow_mut().moved(ev.pointer_id(), point) {
+                Some(GestureUpdate::Move(x, y)) => move_to((x, y)),
+                Some(GestureUpdate::Rotate(degrees)) => player.rotation.set(degrees),
+                None => {}
+            }
+        }
+    };
+    // The focused name tag moves with the arrow keys and rotates with R, for keyboard users.
+    let on_name_tag_key = {
+        let player = player.clone();
+        move |ev: web_sys::KeyboardEvent| {
+            let step = match config.with_untracked(|c| c.snap_grid.min(MAX_SNAP_GRID)) {
+                0 => 10,
+                grid => grid as i32,
+            } * if ev.shift_key() { 5 } else { 1 };
+            let (x, y) = player.position.get_untracked();
+            match ev.key().as_str() {
+                "ArrowLeft" => move_to((x.saturating_sub(step), y)),
+                "ArrowRight" => move_to((x.saturating_add(step), y)),
+                "ArrowUp" => move_to((x, y.saturating_sub(step))),
+                "ArrowDown" => move_to((x, y.saturating_add(step))),
+                "r" => player.rotation.update(|r| *r += 45.0),
+                "R" => player.rotation.update(|r| *r -= 45.0),
+                _ => return,
+            }
             ev.prevent_default();
-
-            let player3 = player2.clone();
-            let move_handler = {
-                let p_dragging = Rc::clone(&p_dragging);
-                let current_panel_size = Rc::clone(&current_panel_size);
-
-                wasm_bindgen::closure::Closure::wrap(Box::new(move |ev: web_sys::MouseEvent| {
-                    if *p_dragging.borrow() {
-                        let (x, y, panel_width, panel_height) = *current_panel_size.borrow();
-                        let new_x = (ev.client_x() - offset_x)
-                            .max(x + 20)
-                            .min(x + panel_width - 20);
-                        let new_y = (ev.client_y() - offset_y)
-                            .max(y + 20)
-                            .min(y + panel_height - 20);
-                        player3.position.set((new_x, new_y));
-                    }
-                }) as Box<dyn FnMut(_)>)
-            };
-
-            let player_name = player2.name.get().clone();
-            let up_handler =
-                wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::MouseEvent| {
-                    if *p_dragging.borrow() {
-                        logging::log!("handle_mouse_up for player {}", player_name);
-                        p_dragging.replace(false);
-                    }
-                }) as Box<dyn FnMut(_)>);
-
-            document
-                .add_event_listener_with_callback(
-                    "mousemove",
-                    move_handler.as_ref().unchecked_ref(),
-                )
-                .unwrap();
-            document
-                .add_event_listener_with_callback("mouseup", up_handler.as_ref().unchecked_ref())
-                .unwrap();
-
-            move_handler.forget();
-            up_handler.forget();
         }
     };
+    let on_pointer_up = move |ev: web_sys::PointerEvent| {
+        if gesture.borrow().is_tracking() {
+            logging::log!("pointer up {}", ev.pointer_id());
+        }
+        gesture.borrow_mut().up(ev.pointer_id());
+    };
+    let on_pointer_cancel = on_pointer_up.clone();
+    // Capture is lost when the panel is re-rendered or removed mid-gesture.
+    let on_lost_capture = on_pointer_up.clone();
 
     let pos = player.position.clone();
     let pos2 = player.position.clone();
-    let rot = player.rotation.clone();
+    let rot = player.rotation;
+    let team = player.team;
+    let team_color =
+        move || team.and_then(|team| config.get().teams.get(team).map(|t| t.color.clone()));
+    let id = player.id;
+    let player_color = move || config.with(|c| c.player_colors.get(id).cloned());
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
 
     view! {
         <div
+            node_ref=container_ref
             style:position="absolute"
             style:left=move || format!("{}px", pos.get().0)
             style:top=move || format!("{}px", pos2.get().1)
             style:transform= move || format!("rotate({}deg)", rot.get())
             class="player-container"
+            style:--team-color=team_color
+            style:--player-color=player_color
+            style:--clock-size=move || format!("{}px", clock.get())
+            on:pointerdown=on_pointer_down
+            on:pointermove=on_pointer_move
+            on:pointerup=on_pointer_up
+            on:pointercancel=on_pointer_cancel
+            on:lostpointercapture=on_lost_capture
         >
             <div class="name-tag-row">
                 <button
                     class="rotation-button"
                     on:click=move |_| { player.rotation.set(player.rotation.get() + 45.0) }
-                    title="Rotate player"
+                    title=move || t("Rotate player")
+                    aria-label=move || t("Rotate {name}").replace("{name}", &player.name.get())
                 ></button>
                 <div
                     class="usertime-name-tag"
-                    on:mousedown=handle_mouse_down
+                    class:usertime-name-tag-team=team.is_some()
                     style="user-select: none;"
-                    title="Drag to move"
+                    title=move || t("Drag to move")
+                    tabindex="0"
+                    role="group"
+                    aria-roledescription=move || t("movable panel")
+                    aria-label=move || {
+                        t("{name}, arrow keys move, R rotates").replace("{name}", &player.name.get())
+                    }
+                    on:keydown=on_name_tag_key
                 >
-                    <p>{move || format!("{}", player.name.get())}</p>
+                    <p>{move || player.name.get()}</p>
                 </div>
+                {move || {
+                    let percent = config.with(|c| c.handicaps.get(id).copied().unwrap_or(100));
+                    (percent != 100)
+                        .then(|| {
+                            view! {
+                                <span class="usertime-handicap" title=move || t("Time allowance (%)")>
+                                    {format!("{}%", percent)}
+                                </span>
+                            }
+                        })
+                }}
             </div>
-            <UserTime player active_player player_toggle/>
+            <UserTime player player_toggle banks config/>
         </div>
     }
 }
 
+/// Keeps a panel position at least 20px inside the timer panel.
+fn clamp_to_panel(
+    (pos_x, pos_y): (i32, i32),
+    (x, y, width, height): (i32, i32, i32, i32),
+) -> (i32, i32) {
+    (
+        pos_x.max(x + 20).min(x + width - 20),
+        pos_y.max(y + 20).min(y + height - 20),
+    )
+}
+
 #[derive(Clone)]
 struct Clock {
     global_timer: ReadSignal<f32>,
     start_timer: ReadSignal<f32>,
     active: RwSignal<bool>,
     timer: RwSignal<f32>,
+    /// Time added by hand to the running turn, or to the first turn while the player waits
+    /// for it; cleared when the turn ends.
+    offset: RwSignal<f32>,
 }
 impl Clock {
     fn new(global_timer: ReadSignal<f32>, start_timer: ReadSignal<f32>) -> Self {
@@ -275,10 +932,12 @@ impl Clock {
             start_timer,
             active: RwSignal::new(false),
             timer: RwSignal::new(0.0),
+            offset: RwSignal::new(0.0),
         };
         Effect::new(move || {
             if m.active.get() {
-                m.timer.set(m.global_timer.get() - m.start_timer.get());
+                m.timer
+                    .set(m.global_timer.get() - m.start_timer.get() + m.offset.get());
             }
         });
         m
@@ -288,26 +947,10 @@ impl Clock {
 #[component]
 fn UserTime(
     player: Player,
-    active_player: ReadSignal<usize>,
     mut player_toggle: impl FnMut() + 'static,
+    banks: Memo<Vec<Option<f32>>>,
+    config: RwSignal<Config>,
 ) -> impl IntoView {
-    Effect::new(move || {
-        player.clock.active.set(player.id == active_player.get());
-        logging::log!("active_player {}", active_player.get());
-    });
-
-    let mut toggle = move || {
-        player_toggle();
-        player
-            .time
-            .update(|timer| timer.push(player.clock.timer.get()));
-        logging::log!(
-            "pushing time on player {}: t{}",
-            player.id,
-            player.time.get().last().unwrap()
-        );
-    };
-
     view! {
         <button
             class=move || {
@@ -318,73 +961,249 @@ fn UserTime(
                 }
             }
 
-            style:color="#1a1a1a"
+            aria-label=move || {
+                let time = config.with(|c| c.format_time(player.clock.timer.get()));
+                if player.clock.active.get() {
+                    config
+                        .with(|c| c.locale.text("{name} {time}, end turn"))
+                        .replace("{name}", &player.name.get())
+                        .replace("{time}", &time)
+                } else {
+                    format!("{} {}", player.name.get(), time)
+                }
+            }
+            aria-disabled=move || (!player.clock.active.get()).to_string()
             on:click=move |_| {
                 if player.clock.active.get() {
-                    toggle();
+                    player_toggle();
                 }
             }
         >
-            <p>{move || format!("{:.1}", player.clock.timer.get())}</p>
+            <p>{move || config.with(|c| c.format_time(player.clock.timer.get()))}</p>
+            <BankTime banks seat=player.id config />
         </button>
     }
 }
 
+/// Time left in a seat's bank, shown only when banks are enabled.
+#[component]
+fn BankTime(banks: Memo<Vec<Option<f32>>>, seat: usize, config: RwSignal<Config>) -> impl IntoView {
+    move || {
+        banks.get().get(seat).copied().flatten().map(|left| {
+            view! {
+                <p class="usertime-bank" class:usertime-bank-empty=left <= 0.0>
+                    {config.with(|c| c.format_time(left.max(0.0)))}
+                </p>
+            }
+        })
+    }
+}
+
 #[component]
-fn TimeTable(players: RwSignal<Vec<Player>>) -> impl IntoView {
+fn TimeTable(
+    players: RwSignal<Vec<Player>>,
+    config: RwSignal<Config>,
+    log: RwSignal<Vec<LogEntry>>,
+    global_timer: ReadSignal<f32>,
+) -> impl IntoView {
+    let rounds = move || {
+        players
+            .get()
+            .into_iter()
+            .map(|player| player.time.get().len())
+            .max()
+            .unwrap_or(0)
+    };
+    let format_time = move |t: f32| config.with(|c| c.format_time(t));
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    // Seat and round of the turn being edited.
+    let cell = RwSignal::new(None::<(usize, usize)>);
+    let track_scores = move || config.with(|c| c.track_scores);
+    let winners = Memo::new(move |_| {
+        let scores: Vec<_> = players.get().iter().map(|player| player.score()).collect();
+        score::winners(&scores, config.with(|c| c.lowest_wins))
+    });
+    let score_input = move |value: Signal<Option<i32>>, set: Box<dyn Fn(Option<i32>)>| {
+        view! {
+            <td>
+                <input
+                    type="number"
+                    class="time-table-score-input"
+                    prop:value=move || value.get().map(|score| score.to_string()).unwrap_or_default()
+                    on:change=move |ev| set(event_target_value(&ev).trim().parse().ok())
+                />
+            </td>
+        }
+    };
+
     view! {
         <div class="time-table-container">
             <table class="time-table">
                 <thead>
                     <tr>
-                        <th>"Name"</th>
+                        <th>{move || t("Name")}</th>
                         {move || {
-                            let rounds = players
-                                .get()
-                                .into_iter()
-                                .map(|player| { player.time.get().len() })
-                                .max()
-                                .unwrap_or(0);
-                            (0..rounds)
-                                .into_iter()
+                            (0..rounds())
                                 .map(|i| {
-                                    view! { <th>{format!("Round {}", i + 1)}</th> }
+                                    view! { <th>{t("Round {n}").replace("{n}", &(i + 1).to_string())}</th> }
                                 })
                                 .collect_view()
                         }}
+                        <th>{move || track_scores().then(|| t("Final"))}</th>
+                        <th>{move || t("Total")}</th>
                     </tr>
                 </thead>
                 <tbody>
+                    <For each=move || players.get() key=|player| player.name let:player>
+                        {
+                            let seat = player.id;
+                            let color = move || config.with(|c| c.player_colors.get(seat).cloned());
+                            let score = Signal::derive({
+                                let player = player.clone();
+                                move || player.score()
+                            });
+                            view! {
+                                <tr style:--player-color=color>
+                                    <td>{player.name}</td>
+                                    {move || {
+                                        let times = player.time.get();
+                                        let total: f32 = times.iter().map(|turn| turn.seconds).sum();
+                                        // Rounds a player has no turn for, and the next one, stay
+                                        // clickable to insert a turn.
+                                        let recorded = times.len();
+                                        let missing = rounds() + 1 - recorded;
+                                        view! {
+                                            {times
+                                                .into_iter()
+                                                .enumerate()
+                                                .map(|(round, turn)| {
+                                                    view! {
+                                                        <td
+                                                            class="time-table-cell"
+                                                            class:time-table-edited=turn.edited
+                                                            class:time-table-selected=move || {
+                                                                cell.get() == Some((seat, round))
+                                                            }
+                                                            tabindex="0"
+                                                            on:click=move |_| cell.set(Some((seat, round)))
+                                                            on:keydown=move |ev| {
+                                                                if ev.key() == "Enter" {
+                                                                    cell.set(Some((seat, round)));
+                                                                }
+                                                            }
+                                                        >
+                                                            {format_time(turn.seconds)}
+                                                        </td>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                            {(0..missing)
+                                                .map(|k| {
+                                                    let round = recorded + k;
+                                                    view! {
+                                                        <td
+                                                            class="time-table-cell time-table-missing"
+                                                            tabindex="0"
+                                                            title=move || t("Insert turn")
+                                                            on:click=move |_| cell.set(Some((seat, round)))
+                                                            on:keydown=move |ev| {
+                                                                if ev.key() == "Enter" {
+                                                                    cell.set(Some((seat, round)));
+                                                                }
+                                                            }
+                                                        >
+                                                            "+"
+                                                        </td>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                            <td>{format_time(total)}</td>
+                                        }
+                                    }}
+                                </tr>
+                                // Score inputs are kept while turns are recorded, so that
+                                // focus and a half-typed score survive the end of a turn.
+                                <Show when=track_scores>
+                                    <tr class="time-table-score-row">
+                                        <td>{move || t("Score")}</td>
+                                        <For each=move || 0..rounds() key=|round| *round let:round>
+                                            {score_input(
+                                                Signal::derive(move || {
+                                                    player.scores.with(|scores| scores.get(round).copied().flatten())
+                                                }),
+                                                Box::new(move |score| {
+                                                    player
+                                                        .scores
+                                                        .update(|scores| {
+                                                            if scores.len() <= round {
+                                                                scores.resize(round + 1, None);
+                                                            }
+                                                            scores[round] = score;
+                                                        })
+                                                }),
+                                            )}
+                                        </For>
+                                        {score_input(
+                                            player.final_score.into(),
+                                            Box::new(move |score| player.final_score.set(score)),
+                                        )}
+                                        <td
+                                            class:time-table-winner=move || {
+                                                winners.with(|w| w.contains(&seat))
+                                            }
+                                            title=move || {
+                                                winners.with(|w| w.contains(&seat)).then(|| t("Winner"))
+                                            }
+                                        >
+                                            {move || score.get().map(|score| score.to_string()).unwrap_or_default()}
+                                        </td>
+                                    </tr>
+                                </Show>
+                            }
+                        }
+                    </For>
                     {move || {
-                        players
+                        let players = players.get();
+                        let rounds = rounds();
+                        config
                             .get()
+                            .teams
                             .into_iter()
-                            .map(|player| {
+                            .enumerate()
+                            .map(|(team, info)| {
+                                let members: Vec<Vec<Turn>> = players
+                                    .iter()
+                                    .filter(|player| player.team == Some(team))
+                                    .map(|player| player.time.get())
+                                    .collect();
+                                let per_round: Vec<f32> = (0..rounds)
+                                    .map(|i| {
+                                        members
+                                            .iter()
+                                            .filter_map(|times| times.get(i))
+                                            .map(|turn| turn.seconds)
+                                            .sum()
+                                    })
+                                    .collect();
+                                let total: f32 = per_round.iter().sum();
                                 view! {
-                                    <tr>
-                                        <td>{player.name}</td>
-                                        {player
-                                            .time
-                                            .get()
+                                    <tr class="time-table-team-row" style:--team-color=info.color>
+                                        <td>{info.name}</td>
+                                        {per_round
                                             .into_iter()
-                                            .map(|t| {
-                                                let s: String;
-                                                if t > 60.0 {
-                                                    s = format!("{}m:{}s", t as u64 / 60, t as u64 % 60);
-                                                } else {
-                                                    s = format!("{}s", t as u64);
-                                                }
-                                                view! { <td>{s}</td> }
-                                            })
+                                            .map(|t| view! { <td>{format_time(t)}</td> })
                                             .collect_view()}
+                                        <td></td>
+                                        <td>{format_time(total)}</td>
                                     </tr>
                                 }
                             })
                             .collect_view()
                     }}
-
                 </tbody>
             </table>
+            <TurnEditor players config log global_timer cell />
+            <GameLog log config />
         </div>
     }
 }
@@ -0,0 +1,70 @@
+use super::i18n::Locale;
+use leptos::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+
+/// A physical button assigned to a seat.
+///
+/// USB button boxes that emulate a keyboard (in the browser and in the desktop build alike)
+/// arrive as ordinary key presses, gamepads and arcade encoders through the Gamepad API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SeatButton {
+    Gamepad { pad: u32, button: u32 },
+    Key(String),
+}
+
+impl SeatButton {
+    pub fn label(&self, locale: Locale) -> String {
+        match self {
+            SeatButton::Gamepad { pad, button } => locale
+                .text("Pad {pad} #{button}")
+                .replace("{pad}", &(pad + 1).to_string())
+                .replace("{button}", &button.to_string()),
+            SeatButton::Key(key) => locale
+                .text("Key {key}")
+                .replace("{key}", &super::keyboard::key_label(key, locale)),
+        }
+    }
+}
+
+/// Seat a key press is assigned to, if any.
+pub fn seat_for_key(buttons: &[Option<SeatButton>], key: &str) -> Option<usize> {
+    buttons.iter().position(
+        |button| matches!(button, Some(SeatButton::Key(bound)) if bound.eq_ignore_ascii_case(key)),
+    )
+}
+
+/// Polls the connected gamepads and calls `on_press` once for every button that goes down.
+///
+/// The Gamepad API has no button events, so the state is sampled on an interval that is
+/// cleared when the calling owner is cleaned up.
+pub fn watch_gamepads(on_press: impl Fn(SeatButton) + 'static) {
+    let pressed = RefCell::new(HashSet::new());
+    let poll = move || {
+        let Ok(pads) = window().navigator().get_gamepads() else {
+            return;
+        };
+        let mut down = HashSet::new();
+        // Disconnected slots are reported as null and fail the cast.
+        for pad in pads
+            .iter()
+            .filter_map(|pad| pad.dyn_into::<web_sys::Gamepad>().ok())
+        {
+            for (i, button) in pad.buttons().iter().enumerate() {
+                if button.unchecked_into::<web_sys::GamepadButton>().pressed() {
+                    down.insert((pad.index(), i as u32));
+                }
+            }
+        }
+        let new_presses: Vec<_> = down.difference(&pressed.borrow()).copied().collect();
+        *pressed.borrow_mut() = down;
+        for (pad, button) in new_presses {
+            on_press(SeatButton::Gamepad { pad, button });
+        }
+    };
+    if let Ok(handle) = set_interval_with_handle(poll, Duration::from_millis(30)) {
+        on_cleanup(move || handle.clear());
+    }
+}
@@ -1,79 +1,1232 @@
-use leptos::{logging, prelude::*};
+use super::buttons::{SeatButton, watch_gamepads};
+use super::duration::TimeFormat;
+use super::i18n::Locale;
+use super::keyboard::{KeyBindings, is_bindable, key_label};
+use super::layout::{Layout, panel_size, seat_positions};
+use super::sound::{self, AlertSettings, Sound};
+use super::speech::{self, SpeechSettings};
+use super::summary::Archive;
+use super::team::{self, Team};
+use super::theme::{self, Theme};
+use leptos::{ev, logging, prelude::*};
+use std::collections::HashMap;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use web_sys::js_sys::Math;
+
+pub const MIN_PLAYERS: usize = 1;
+pub const MAX_PLAYERS: usize = 20;
+/// Coarsest grid name tags can snap to, in pixels.
+pub const MAX_SNAP_GRID: u32 = 200;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub nplayers: usize,
     pub names: Vec<String>,
     pub game_counter: usize,
+    pub teams: Vec<Team>,
+    pub player_teams: Vec<Option<usize>>,
+    pub alternate_teams: bool,
+    /// Total seconds each player (or team sharing a bank) may use, 0 disables the bank.
+    pub time_bank: u32,
+    pub keys: KeyBindings,
+    pub seat_buttons: Vec<Option<SeatButton>>,
+    pub layout: Layout,
+    /// Name of the table, used to keep separate saved seatings.
+    pub table: String,
+    /// Grid size in pixels that dragged panels snap to, 0 disables snapping.
+    pub snap_grid: u32,
+    /// Keep dragged panels off each other and off the controls and table.
+    pub avoid_overlap: bool,
+    /// Show only the active player's clock, for games played on one phone.
+    pub single_device: bool,
+    pub time_format: TimeFormat,
+    pub alerts: AlertSettings,
+    pub speech: SpeechSettings,
+    /// Stop the screen from dimming or locking while a game is running.
+    pub keep_awake: bool,
+    pub theme: Theme,
+    /// Size of all text in percent of the default.
+    pub font_scale: u32,
+    /// Color of each seat, shown on its panel and in the time table.
+    pub player_colors: Vec<String>,
+    /// Seat that takes the first turn of a new game.
+    pub first_player: usize,
+    /// Rounds after which the game ends, 0 for no limit.
+    pub rounds: u32,
+    /// Seconds of game time after which the game ends, 0 for no limit.
+    pub time_limit: u32,
+    /// Seconds without any interaction after which a running game pauses, 0 disables it.
+    pub idle_pause: u32,
+    /// Time allowance of each seat in percent, scaling its time bank (150 = half as much again).
+    pub handicaps: Vec<u32>,
+    /// Enter scores in the time table.
+    pub track_scores: bool,
+    /// The lowest score wins instead of the highest.
+    pub lowest_wins: bool,
+    pub locale: Locale,
 }
 
 impl Config {
     pub fn new() -> Self {
+        Self::with_locale(Locale::detect())
+    }
+
+    pub fn with_locale(locale: Locale) -> Self {
         Self {
             nplayers: 0,
             names: vec![],
             game_counter: 0,
+            teams: vec![],
+            player_teams: vec![],
+            alternate_teams: false,
+            time_bank: 0,
+            keys: KeyBindings::new(),
+            seat_buttons: vec![],
+            layout: Layout::Free,
+            table: String::new(),
+            snap_grid: 0,
+            avoid_overlap: false,
+            single_device: false,
+            time_format: TimeFormat::MinutesSecondsTenths,
+            alerts: AlertSettings::new(),
+            speech: SpeechSettings::new(locale),
+            keep_awake: true,
+            theme: Theme::Dark,
+            font_scale: 100,
+            player_colors: vec![],
+            first_player: 0,
+            rounds: 0,
+            time_limit: 0,
+            idle_pause: 0,
+            handicaps: vec![],
+            track_scores: false,
+            lowest_wins: false,
+            locale,
         }
         //Self {nplayers: 2, names: vec!["Player 1".to_string(), "Player 2".to_string()]}
     }
+
+    /// Formats a duration in the chosen format, with the locale's decimal separator.
+    pub fn format_time(&self, seconds: f32) -> String {
+        self.time_format
+            .format(seconds)
+            .replace('.', self.locale.decimal_separator())
+    }
+
+    /// Name shown for `seat`, "Player N" when it was left blank.
+    pub fn player_name(&self, seat: usize) -> String {
+        match self.names.get(seat).map(|name| name.trim()) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => self
+                .locale
+                .text("Player {n}")
+                .replace("{n}", &(seat + 1).to_string()),
+        }
+    }
+
+    /// Time allowance of `seat` as a factor, 1.0 without a handicap.
+    pub fn handicap(&self, seat: usize) -> f32 {
+        self.handicaps.get(seat).copied().unwrap_or(100) as f32 / 100.0
+    }
+
+    /// Sets each seat's allowance from the average turn of that player in past games,
+    /// relative to everyone's average, so slower players get more time.
+    /// Players without history keep their current allowance.
+    pub fn handicaps_from_history(&mut self, averages: &HashMap<String, f32>) {
+        if averages.is_empty() {
+            return;
+        }
+        let overall = averages.values().sum::<f32>() / averages.len() as f32;
+        if overall <= 0.0 {
+            return;
+        }
+        for seat in 0..self.nplayers {
+            if let Some(average) = averages.get(&self.player_name(seat)) {
+                // Rounded to 10% steps, which is as precise as a few games allow.
+                let percent = (average / overall * 10.0).round() as u32 * 10;
+                self.handicaps[seat] = percent.clamp(50, 300);
+            }
+        }
+    }
+
+    /// Changes the number of seats, keeping the settings of the seats that remain.
+    pub fn set_player_count(&mut self, count: usize) {
+        self.nplayers = count;
+        self.names.resize(count, String::new());
+        self.player_teams.resize(count, None);
+        self.seat_buttons.resize(count, None);
+        self.handicaps.resize(count, 100);
+        let len = self.player_colors.len();
+        self.player_colors.truncate(count);
+        self.player_colors
+            .extend((len..count).map(theme::player_color));
+        if self.first_player >= count {
+            self.first_player = 0;
+        }
+    }
+
+    /// Rearranges the seats so that seat `k` gets the player that sat at `order[k]`.
+    ///
+    /// Physical seat buttons are fixed to the table, so they stay with the seat.
+    fn reorder(&mut self, order: &[usize]) {
+        fn apply<T: Clone>(items: &mut Vec<T>, order: &[usize]) {
+            *items = order.iter().map(|&seat| items[seat].clone()).collect();
+        }
+        apply(&mut self.names, order);
+        apply(&mut self.player_teams, order);
+        apply(&mut self.player_colors, order);
+        apply(&mut self.handicaps, order);
+        self.first_player = order
+            .iter()
+            .position(|&seat| seat == self.first_player)
+            .unwrap_or(0);
+    }
+
+    /// Moves the player at seat `from` to seat `to`, shifting the players in between.
+    pub fn move_seat(&mut self, from: usize, to: usize) {
+        if from >= self.nplayers || to >= self.nplayers {
+            return;
+        }
+        let mut order: Vec<usize> = (0..self.nplayers).collect();
+        let seat = order.remove(from);
+        order.insert(to, seat);
+        self.reorder(&order);
+    }
+
+    /// Seats the players in a random order.
+    pub fn shuffle_seats(&mut self) {
+        let mut order: Vec<usize> = (0..self.nplayers).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, random_below(i + 1));
+        }
+        self.reorder(&order);
+    }
+
+    /// Switches the language, translating announcements and team names still at their default.
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.speech.translate_defaults(self.locale, locale);
+        for (index, team) in self.teams.iter_mut().enumerate() {
+            if team.name == team::default_name(index, self.locale) {
+                team.name = team::default_name(index, locale);
+            }
+        }
+        self.locale = locale;
+    }
+
+    /// Seats whose name is already used by an earlier seat, ignoring case.
+    pub fn duplicate_names(&self) -> Vec<usize> {
+        let names: Vec<String> = (0..self.nplayers)
+            .map(|seat| self.player_name(seat).to_lowercase())
+            .collect();
+        (0..names.len())
+            .filter(|&seat| names[..seat].contains(&names[seat]))
+            .collect()
+    }
+
+    pub fn team_of(&self, seat: usize) -> Option<usize> {
+        self.player_teams
+            .get(seat)
+            .copied()
+            .flatten()
+            .filter(|&team| team < self.teams.len())
+    }
+
+    /// English setup with `nplayers` default players, shared by the unit tests.
+    #[cfg(test)]
+    pub fn for_test(nplayers: usize) -> Self {
+        let mut config = Self::with_locale(Locale::English);
+        config.set_player_count(nplayers);
+        config
+    }
+}
+
+/// Uniformly distributed random number in `0..n`.
+fn random_below(n: usize) -> usize {
+    ((Math::random() * n as f64) as usize).min(n.saturating_sub(1))
 }
 
 #[component]
 pub fn Configuration(config_signal: RwSignal<Config>) -> impl IntoView {
     let config = RwSignal::new(Config::new());
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    let count_error = RwSignal::new(false);
+
+    // Seat being dragged to a new place in the player list.
+    let dragging = RwSignal::new(None::<usize>);
+    let drag_over = move |ev: web_sys::PointerEvent| {
+        let Some(from) = dragging.get_untracked() else {
+            return;
+        };
+        // Pointer capture keeps the events on the handle, so look up the row underneath.
+        let to = document()
+            .element_from_point(ev.client_x() as f32, ev.client_y() as f32)
+            .and_then(|element| element.closest("[data-seat]").ok().flatten())
+            .and_then(|row| row.get_attribute("data-seat"))
+            .and_then(|seat| seat.parse().ok());
+        if let Some(to) = to.filter(|&to| to != from) {
+            config.update(|c| c.move_seat(from, to));
+            dragging.set(Some(to));
+        }
+    };
+    // Seat highlighted while the first player is being drawn.
+    let drawing = RwSignal::new(None::<usize>);
+    let draw_first_player = move || {
+        let n = config.with_untracked(|c| c.nplayers);
+        if n == 0 || drawing.get_untracked().is_some() {
+            return;
+        }
+        // Run a few laps around the table, slowing down until the drawn seat comes up.
+        let winner = random_below(n);
+        let steps = 2 * n + winner + 1;
+        let mut delay = 0;
+        for step in 0..steps {
+            delay += 40 + 200 * step * step / (steps * steps);
+            set_timeout(
+                move || drawing.set(Some(step % n)),
+                Duration::from_millis(delay as u64),
+            );
+        }
+        set_timeout(
+            move || {
+                config.update(|c| c.first_player = winner);
+                drawing.set(None);
+            },
+            Duration::from_millis(delay as u64 + 600),
+        );
+    };
+    let duplicates = Memo::new(move |_| config.with(|c| c.duplicate_names()));
+    let can_save = move || {
+        !count_error.get() && duplicates.with(|d| d.is_empty()) && config.with(|c| c.nplayers > 0)
+    };
+    // Everything but the game counter, which only changes when saving.
+    let unsaved = move || {
+        let mut saved = config_signal.get();
+        saved.game_counter = config.with(|c| c.game_counter);
+        config.with(|c| *c != saved)
+    };
+
+    // Seat waiting for its button to be pressed on the mapping screen.
+    let learning = RwSignal::new(None::<usize>);
+    let assign = move |button: SeatButton| {
+        if let Some(seat) = learning.get_untracked() {
+            logging::log!("Assigned {:?} to player {}", button, seat);
+            config.update(|c| {
+                // A button drives a single seat, so drop it from wherever it was before.
+                for bound in c.seat_buttons.iter_mut() {
+                    if bound.as_ref() == Some(&button) {
+                        *bound = None;
+                    }
+                }
+                // The seat may be gone if the player count shrank while it was learning.
+                if let Some(bound) = c.seat_buttons.get_mut(seat) {
+                    *bound = Some(button);
+                }
+            });
+            learning.set(None);
+        }
+    };
+    watch_gamepads(assign);
+    let keydown = window_event_listener(ev::keydown, move |ev| {
+        if learning.get_untracked().is_some() && is_bindable(&ev.key()) {
+            ev.prevent_default();
+            if ev.key() == "Escape" {
+                learning.set(None);
+            } else {
+                assign(SeatButton::Key(ev.key()));
+            }
+        }
+    });
+    on_cleanup(move || keydown.remove());
+
     view! {
         <div class="config-section config-container">
             <div class="config-layout">
                 <div class="config-left">
-                    <div class="config-label">Number of Players</div>
+                    <div class="config-label">{move || t("Number of Players")}</div>
                     <input
                         type="number"
                         class="config-input config-player-number"
+                        class:config-input-invalid=move || count_error.get()
+                        min=MIN_PLAYERS
+                        max=MAX_PLAYERS
                         on:input=move |ev| {
-                            if let Ok(num) = event_target_value(&ev).parse() {
-                                config.update(|c| c.nplayers = num);
-                                config.update(|c| c.names.resize(num, String::new()));
-                                logging::log!(
-                                    "Number of players changed to {}", config.get().nplayers
-                                );
+                            match event_target_value(&ev).parse() {
+                                Ok(num) if (MIN_PLAYERS..=MAX_PLAYERS).contains(&num) => {
+                                    count_error.set(false);
+                                    config.update(|c| c.set_player_count(num));
+                                    logging::log!(
+                                        "Number of players changed to {}", config.get().nplayers
+                                    );
+                                }
+                                _ => count_error.set(true),
                             }
                         }
                         prop:value=move || config.get().nplayers.to_string()
                     />
+                    <Show when=move || count_error.get()>
+                        <div class="config-error">
+                            {move || {
+                                t("Enter between {min} and {max} players")
+                                    .replace("{min}", &MIN_PLAYERS.to_string())
+                                    .replace("{max}", &MAX_PLAYERS.to_string())
+                            }}
+                        </div>
+                    </Show>
+                    <div class="config-label">{move || t("Number of Teams")}</div>
+                    <input
+                        type="number"
+                        class="config-input"
+                        min="0"
+                        max=MAX_PLAYERS
+                        on:input=move |ev| {
+                            if let Ok(num) = event_target_value(&ev).parse::<usize>() {
+                                let num = num.min(MAX_PLAYERS);
+                                config.update(|c| {
+                                    let len = c.teams.len();
+                                    c.teams.truncate(num);
+                                    let locale = c.locale;
+                                    c.teams.extend((len..num).map(|index| Team::new(index, locale)));
+                                });
+                                logging::log!("Number of teams changed to {}", num);
+                            }
+                        }
+                        prop:value=move || config.get().teams.len().to_string()
+                    />
+                    <label class="config-label">
+                        <input
+                            type="checkbox"
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                config.update(|c| c.alternate_teams = checked);
+                            }
+                            prop:checked=move || config.get().alternate_teams
+                        />
+                        " "
+                        {move || t("Alternate turns between teams")}
+                    </label>
+                    <div class="config-label">{move || t("Time bank (minutes, 0 = off)")}</div>
+                    <input
+                        type="number"
+                        class="config-input"
+                        on:input=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
+                                config.update(|c| c.time_bank = minutes.saturating_mul(60));
+                            }
+                        }
+                        prop:value=move || (config.get().time_bank / 60).to_string()
+                    />
+                    <div class="config-label">{move || t("Rounds (0 = no limit)")}</div>
+                    <input
+                        type="number"
+                        min="0"
+                        class="config-input"
+                        on:input=move |ev| {
+                            if let Ok(rounds) = event_target_value(&ev).parse() {
+                                config.update(|c| c.rounds = rounds);
+                            }
+                        }
+                        prop:value=move || config.get().rounds.to_string()
+                    />
+                    <div class="config-label">{move || t("Game time limit (minutes, 0 = off)")}</div>
+                    <input
+                        type="number"
+                        min="0"
+                        class="config-input"
+                        on:input=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
+                                config.update(|c| c.time_limit = minutes.saturating_mul(60));
+                            }
+                        }
+                        prop:value=move || (config.get().time_limit / 60).to_string()
+                    />
+                    <div class="config-label">{move || t("Pause when idle (minutes, 0 = off)")}</div>
+                    <input
+                        type="number"
+                        min="0"
+                        class="config-input"
+                        on:input=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse::<u32>() {
+                                config.update(|c| c.idle_pause = minutes.saturating_mul(60));
+                            }
+                        }
+                        prop:value=move || (config.get().idle_pause / 60).to_string()
+                    />
+                    <label class="config-label">
+                        <input
+                            type="checkbox"
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                config.update(|c| c.track_scores = checked);
+                            }
+                            prop:checked=move || config.get().track_scores
+                        />
+                        " "
+                        {move || t("Keep score")}
+                    </label>
+                    <Show when=move || config.get().track_scores>
+                        <label class="config-label">
+                            <input
+                                type="checkbox"
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    config.update(|c| c.lowest_wins = checked);
+                                }
+                                prop:checked=move || config.get().lowest_wins
+                            />
+                            " "
+                            {move || t("Lowest score wins")}
+                        </label>
+                    </Show>
+                    <label class="config-label">
+                        <input
+                            type="checkbox"
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                config.update(|c| c.single_device = checked);
+                            }
+                            prop:checked=move || config.get().single_device
+                        />
+                        " "
+                        {move || t("Pass-the-phone mode")}
+                    </label>
+                    <label class="config-label">
+                        <input
+                            type="checkbox"
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                config.update(|c| c.keep_awake = checked);
+                            }
+                            prop:checked=move || config.get().keep_awake
+                        />
+                        " "
+                        {move || t("Keep screen on during games")}
+                    </label>
+                    <div class="config-label">{move || t("Time format")}</div>
+                    <select
+                        class="field-select"
+                        on:change=move |ev| {
+                            let key = event_target_value(&ev);
+                            config.update(|c| c.time_format = TimeFormat::from_key(&key));
+                        }
+                        prop:value=move || config.get().time_format.key()
+                    >
+                        {TimeFormat::ALL
+                            .into_iter()
+                            .map(|(key, label)| view! { <option value=key>{move || t(label)}</option> })
+                            .collect_view()}
+                    </select>
+                    <div class="config-label">{move || t("Language")}</div>
+                    <select
+                        class="field-select"
+                        on:change=move |ev| {
+                            let key = event_target_value(&ev);
+                            config.update(|c| c.set_locale(Locale::from_key(&key)));
+                        }
+                        prop:value=move || config.get().locale.key()
+                    >
+                        {Locale::ALL
+                            .into_iter()
+                            .map(|(key, label)| view! { <option value=key>{label}</option> })
+                            .collect_view()}
+                    </select>
+                    <div class="config-label">{move || t("Theme")}</div>
+                    <select
+                        class="field-select"
+                        on:change=move |ev| {
+                            let key = event_target_value(&ev);
+                            config.update(|c| c.theme = Theme::from_key(&key));
+                        }
+                        prop:value=move || config.get().theme.key()
+                    >
+                        {Theme::ALL
+                            .into_iter()
+                            .map(|(key, label)| view! { <option value=key>{move || t(label)}</option> })
+                            .collect_view()}
+                    </select>
+                    <div class="config-label">{move || t("Text size (%)")}</div>
+                    <input
+                        type="number"
+                        class="config-input"
+                        min="50"
+                        max="300"
+                        step="10"
+                        on:input=move |ev| {
+                            if let Ok(scale) = event_target_value(&ev).parse::<u32>() {
+                                config.update(|c| c.font_scale = scale.clamp(50, 300));
+                            }
+                        }
+                        prop:value=move || config.get().font_scale.to_string()
+                    />
+                    <div class="config-label">{move || t("Seating")}</div>
+                    <select
+                        class="field-select"
+                        on:change=move |ev| {
+                            let key = event_target_value(&ev);
+                            config.update(|c| c.layout = Layout::from_key(&key, 2));
+                        }
+                        prop:value=move || config.get().layout.key()
+                    >
+                        {Layout::ALL
+                            .into_iter()
+                            .map(|(key, label)| view! { <option value=key>{move || t(label)}</option> })
+                            .collect_view()}
+                    </select>
+                    {move || match config.get().layout {
+                        Layout::Rectangle { per_side } => {
+                            Some(
+                                view! {
+                                    <label class="config-key-row">
+                                        <span class="field-label">{move || t("Seats per side")}</span>
+                                        <input
+                                            type="number"
+                                            min="1"
+                                            class="config-input"
+                                            on:input=move |ev| {
+                                                if let Ok(per_side) = event_target_value(&ev).parse() {
+                                                    config.update(|c| c.layout = Layout::Rectangle { per_side });
+                                                }
+                                            }
+                                            prop:value=per_side.to_string()
+                                        />
+                                    </label>
+                                },
+                            )
+                        }
+                        _ => None,
+                    }}
+                    <label class="config-key-row">
+                        <span class="field-label">{move || t("Snap to grid (px)")}</span>
+                        <input
+                            type="number"
+                            min="0"
+                            max=MAX_SNAP_GRID
+                            class="config-input"
+                            on:input=move |ev| {
+                                if let Ok(grid) = event_target_value(&ev).parse::<u32>() {
+                                    config.update(|c| c.snap_grid = grid.min(MAX_SNAP_GRID));
+                                }
+                            }
+                            prop:value=move || config.get().snap_grid.to_string()
+                        />
+                    </label>
+                    <label class="config-label">
+                        <input
+                            type="checkbox"
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                config.update(|c| c.avoid_overlap = checked);
+                            }
+                            prop:checked=move || config.get().avoid_overlap
+                        />
+                        " "
+                        {move || t("Keep panels from overlapping")}
+                    </label>
+                    <div class="config-label">{move || t("Table")}</div>
+                    <input
+                        type="text"
+                        class="config-text-input"
+                        placeholder=move || t("Default")
+                        on:input=move |ev| {
+                            let table = event_target_value(&ev);
+                            config.update(|c| c.table = table);
+                        }
+                        prop:value=move || config.get().table
+                    />
+                    <div class="config-label">{move || t("Sounds")}</div>
+                    <AlertInput config label="Turn change" sound=Sound::TurnChange />
+                    <AlertInput config label="Warning" sound=Sound::Warning />
+                    <AlertInput config label="Out of time" sound=Sound::Expiry />
+                    <label class="config-key-row">
+                        <span class="field-label">{move || t("Warn at (s)")}</span>
+                        <input
+                            type="text"
+                            class="config-text-input"
+                            placeholder="60, 10"
+                            on:change=move |ev| {
+                                let warning_at = event_target_value(&ev)
+                                    .split(',')
+                                    .filter_map(|at| at.trim().parse().ok())
+                                    .collect();
+                                config.update(|c| c.alerts.warning_at = warning_at);
+                            }
+                            prop:value=move || {
+                                config
+                                    .get()
+                                    .alerts
+                                    .warning_at
+                                    .iter()
+                                    .map(|at| at.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }
+                        />
+                    </label>
+                    <div class="config-label">{move || t("Announcements")}</div>
+                    <SpeechInput config />
+                    <div class="config-label">{move || t("Keyboard shortcuts")}</div>
+                    <KeyInput config label="End turn" binding=|k| &mut k.end_turn />
+                    <KeyInput config label="Pause" binding=|k| &mut k.pause />
+                    <KeyInput config label="Back" binding=|k| &mut k.back />
+                    <KeyInput config label="Help" binding=|k| &mut k.help />
+                    <KeyInput config label="Table mode" binding=|k| &mut k.fullscreen />
                 </div>
                 <div class="config-right">
                     <div class="config-player-list">
                         <For
-                            each=move || (0..config.get().nplayers)
-                            key=move |i| i.clone()
+                            each=move || 0..config.get().nplayers
+                            key=move |i| *i
                             let(child)
                         >
-                            <input
-                                type="text"
-                                class="config-text-input config-player-item"
-                                on:input=move |ev| {
-                                    let name = event_target_value(&ev);
-                                    config.update(|c| c.names[child] = name);
-                                    logging::log!(
-                                        "Player name changed to {}", config.get().names[child as usize]
-                                    );
-                                }
-                                prop:value=move || config.get().names[child as usize].clone()
-                            />
+                            <div
+                                class="config-player-row"
+                                class:config-player-row-dragging=move || dragging.get() == Some(child)
+                                class:config-player-row-drawing=move || drawing.get() == Some(child)
+                                data-seat=child
+                            >
+                                <span
+                                    class="config-drag-handle"
+                                    tabindex="0"
+                                    title=move || t("Drag to reorder")
+                                    aria-label=move || {
+                                        t("Move {name}, arrow keys reorder")
+                                            .replace("{name}", &config.with(|c| c.player_name(child)))
+                                    }
+                                    on:pointerdown=move |ev| {
+                                        ev.prevent_default();
+                                        dragging.set(Some(child));
+                                        if let Some(handle) = ev
+                                            .target()
+                                            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                                        {
+                                            let _ = handle.set_pointer_capture(ev.pointer_id());
+                                        }
+                                    }
+                                    on:pointermove=drag_over
+                                    on:pointerup=move |_| dragging.set(None)
+                                    on:pointercancel=move |_| dragging.set(None)
+                                    on:keydown=move |ev| {
+                                        let to = match ev.key().as_str() {
+                                            "ArrowUp" => child.checked_sub(1),
+                                            "ArrowDown" => Some(child + 1),
+                                            _ => return,
+                                        };
+                                        ev.prevent_default();
+                                        if let Some(to) = to {
+                                            config.update(|c| c.move_seat(child, to));
+                                        }
+                                    }
+                                >
+                                    "⠿"
+                                </span>
+                                <button
+                                    class="config-first-button"
+                                    class:config-first-button-active=move || {
+                                        config.with(|c| c.first_player == child)
+                                    }
+                                    title=move || t("First player")
+                                    aria-pressed=move || config.with(|c| c.first_player == child).to_string()
+                                    on:click=move |_| config.update(|c| c.first_player = child)
+                                >
+                                    "★"
+                                </button>
+                                <input
+                                    type="color"
+                                    class="config-team-color"
+                                    title=move || t("Player color")
+                                    on:input=move |ev| {
+                                        let color = event_target_value(&ev);
+                                        config.update(|c| c.player_colors[child] = color);
+                                    }
+                                    prop:value=move || {
+                                        config.get().player_colors.get(child).cloned().unwrap_or_default()
+                                    }
+                                />
+                                <input
+                                    type="text"
+                                    class="config-text-input config-player-item"
+                                    class:config-input-invalid=move || {
+                                        duplicates.with(|d| d.contains(&child))
+                                    }
+                                    placeholder=move || {
+                                        t("Player {n}").replace("{n}", &(child + 1).to_string())
+                                    }
+                                    title=move || {
+                                        duplicates
+                                            .with(|d| d.contains(&child))
+                                            .then(|| t("Name already used"))
+                                    }
+                                    on:input=move |ev| {
+                                        let name = event_target_value(&ev);
+                                        config.update(|c| c.names[child] = name);
+                                        logging::log!(
+                                            "Player name changed to {}", config.get().names[child]
+                                        );
+                                    }
+                                    prop:value=move || config.get().names[child].clone()
+                                />
+                                <select
+                                    class="field-select"
+                                    on:change=move |ev| {
+                                        let team = event_target_value(&ev).parse().ok();
+                                        config.update(|c| c.player_teams[child] = team);
+                                    }
+                                    prop:value=move || {
+                                        config
+                                            .get()
+                                            .team_of(child)
+                                            .map(|team| team.to_string())
+                                            .unwrap_or_default()
+                                    }
+                                >
+                                    <option value="">{move || t("No team")}</option>
+                                    {move || {
+                                        config
+                                            .get()
+                                            .teams
+                                            .into_iter()
+                                            .enumerate()
+                                            .map(|(i, team)| {
+                                                view! { <option value=i.to_string()>{team.name}</option> }
+                                            })
+                                            .collect_view()
+                                    }}
+                                </select>
+                                <input
+                                    type="number"
+                                    min="50"
+                                    max="300"
+                                    step="10"
+                                    class="config-input config-handicap-input"
+                                    title=move || t("Time allowance (%)")
+                                    aria-label=move || t("Time allowance (%)")
+                                    on:input=move |ev| {
+                                        if let Ok(percent) = event_target_value(&ev).parse::<u32>() {
+                                            config
+                                                .update(|c| c.handicaps[child] = percent.clamp(50, 300));
+                                        }
+                                    }
+                                    prop:value=move || {
+                                        config.with(|c| c.handicaps.get(child).copied().unwrap_or(100).to_string())
+                                    }
+                                />
+                                <button
+                                    class="action-button"
+                                    class:action-button-active=move || learning.get() == Some(child)
+                                    title=move || t("Press to assign a physical button, Escape to cancel")
+                                    on:click=move |_| learning.set(Some(child))
+                                >
+                                    {move || {
+                                        if learning.get() == Some(child) {
+                                            t("Press a button…").to_string()
+                                        } else {
+                                            config
+                                                .with(|c| {
+                                                    c.seat_buttons
+                                                        .get(child)
+                                                        .cloned()
+                                                        .flatten()
+                                                        .map(|button| button.label(c.locale))
+                                                })
+                                                .unwrap_or_else(|| t("Assign button").to_string())
+                                        }
+                                    }}
+                                </button>
+                                <button
+                                    class="config-clear-button"
+                                    title=move || t("Remove button")
+                                    on:click=move |_| config.update(|c| c.seat_buttons[child] = None)
+                                >
+                                    "×"
+                                </button>
+                            </div>
+                        </For>
+                    </div>
+                    <div class="config-key-row">
+                        <button
+                            class="action-button"
+                            on:click=move |_| config.update(|c| c.shuffle_seats())
+                        >
+                            {move || t("Randomize seating")}
+                        </button>
+                        <button
+                            class="action-button"
+                            class:action-button-active=move || drawing.get().is_some()
+                            on:click=move |_| draw_first_player()
+                        >
+                            {move || t("Pick first player")}
+                        </button>
+                        <button
+                            class="action-button"
+                            title=move || t("Slower players get more time, based on past games")
+                            on:click=move |_| {
+                                let averages = Archive::load().average_turns();
+                                config.update(|c| c.handicaps_from_history(&averages));
+                            }
+                        >
+                            {move || t("Handicaps from history")}
+                        </button>
+                    </div>
+                    <Show when=move || duplicates.with(|d| !d.is_empty())>
+                        <div class="config-error">
+                            {move || {
+                                let names = config
+                                    .with(|c| {
+                                        duplicates
+                                            .get()
+                                            .into_iter()
+                                            .map(|seat| c.player_name(seat))
+                                            .collect::<Vec<_>>()
+                                            .join(", ")
+                                    });
+                                format!("{}: {}", t("Name already used"), names)
+                            }}
+                        </div>
+                    </Show>
+                    <SeatingPreview config />
+                    <div class="config-team-list">
+                        <For
+                            each=move || 0..config.get().teams.len()
+                            key=move |i| *i
+                            let(team)
+                        >
+                            <div class="config-team-row">
+                                <input
+                                    type="color"
+                                    class="config-team-color"
+                                    on:input=move |ev| {
+                                        let color = event_target_value(&ev);
+                                        config.update(|c| c.teams[team].color = color);
+                                    }
+                                    prop:value=move || {
+                                        config.get().teams.get(team).map(|t| t.color.clone()).unwrap_or_default()
+                                    }
+                                />
+                                <input
+                                    type="text"
+                                    class="config-text-input"
+                                    on:input=move |ev| {
+                                        let name = event_target_value(&ev);
+                                        config.update(|c| c.teams[team].name = name);
+                                    }
+                                    prop:value=move || {
+                                        config.get().teams.get(team).map(|t| t.name.clone()).unwrap_or_default()
+                                    }
+                                />
+                                <label class="config-label">
+                                    <input
+                                        type="checkbox"
+                                        on:change=move |ev| {
+                                            let checked = event_target_checked(&ev);
+                                            config.update(|c| c.teams[team].shared_bank = checked);
+                                        }
+                                        prop:checked=move || {
+                                            config.get().teams.get(team).is_some_and(|t| t.shared_bank)
+                                        }
+                                    />
+                                    " "
+                                    {move || t("Shared time bank")}
+                                </label>
+                            </div>
                         </For>
                     </div>
                 </div>
             </div>
+            <Show when=unsaved>
+                <div class="config-unsaved">{move || t("Unsaved changes")}</div>
+            </Show>
             <button
                 class="config-save-button"
+                disabled=move || !can_save()
                 on:click=move |_| {
                     logging::log!("Configuration saved, {}", config.get().nplayers);
-                    config.update(|c| c.game_counter = config_signal.get().game_counter + 1);
+                    config.update(|c| {
+                        c.names = (0..c.nplayers).map(|seat| c.player_name(seat)).collect();
+                        c.game_counter = config_signal.get().game_counter + 1;
+                    });
                     config_signal.set(config.get());
                 }
             >
-                "Save"
+                {move || t("Save")}
             </button>
         </div>
     }
 }
+
+/// Miniature of the timer panel showing where the seats of the chosen layout end up.
+#[component]
+fn SeatingPreview(config: RwSignal<Config>) -> impl IntoView {
+    const PANEL: (i32, i32, i32, i32) = (0, 0, 240, 160);
+    const CLOCK: i32 = 20;
+    // Seat markers are centred on the panel they stand for.
+    const SEAT: i32 = 30;
+    let (width, height) = panel_size(CLOCK);
+    let offset = ((width as i32 - SEAT) / 2, (height as i32 - SEAT) / 2);
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+
+    view! {
+        <div class="config-label">{move || t("Seating preview")}</div>
+        {move || {
+            let config = config.get();
+            match seat_positions(config.layout, config.nplayers, PANEL, CLOCK) {
+                Some(seats) => {
+                    view! {
+                        <div
+                            class="config-seating-preview"
+                            style:width=format!("{}px", PANEL.2)
+                            style:height=format!("{}px", PANEL.3)
+                        >
+                            {seats
+                                .into_iter()
+                                .enumerate()
+                                .map(|(seat, ((x, y), _))| {
+                                    view! {
+                                        <div
+                                            class="config-seating-seat"
+                                            style:left=format!("{}px", x + offset.0)
+                                            style:top=format!("{}px", y + offset.1)
+                                            style:background=config.player_colors.get(seat).cloned()
+                                            title=config.player_name(seat)
+                                        >
+                                            {seat + 1}
+                                        </div>
+                                    }
+                                })
+                                .collect_view()}
+                        </div>
+                    }
+                        .into_any()
+                }
+                None => {
+                    view! {
+                        <div class="field-label">
+                            {config.locale.text("Seats are placed by hand")}
+                        </div>
+                    }
+                        .into_any()
+                }
+            }
+        }}
+    }
+}
+
+/// Input that records the next key pressed into one of the key bindings.
+#[component]
+fn KeyInput(
+    config: RwSignal<Config>,
+    label: &'static str,
+    binding: fn(&mut KeyBindings) -> &mut String,
+) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    view! {
+        <label class="config-key-row">
+            <span class="field-label">{move || t(label)}</span>
+            <input
+                type="text"
+                readonly
+                class="config-input config-key-input"
+                title=move || t("Press a key, Escape to cancel")
+                on:keydown=move |ev| {
+                    let key = ev.key();
+                    if !is_bindable(&key) {
+                        return;
+                    }
+                    ev.prevent_default();
+                    if key == "Escape" {
+                        let _ = event_target::<web_sys::HtmlElement>(&ev).blur();
+                        return;
+                    }
+                    config.update(|c| *binding(&mut c.keys) = key);
+                }
+                prop:value=move || key_label(binding(&mut config.get().keys), config.with(|c| c.locale))
+            />
+        </label>
+    }
+}
+
+/// Switch, volume slider and preview button for one of the sound alerts.
+#[component]
+fn AlertInput(config: RwSignal<Config>, label: &'static str, sound: Sound) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    view! {
+        <label class="config-key-row">
+            <input
+                type="checkbox"
+                on:change=move |ev| {
+                    let checked = event_target_checked(&ev);
+                    config.update(|c| c.alerts.alert_mut(sound).enabled = checked);
+                }
+                prop:checked=move || config.get().alerts.alert(sound).enabled
+            />
+            <span class="field-label">{move || t(label)}</span>
+            <input
+                type="range"
+                min="0"
+                max="100"
+                class="config-volume"
+                on:input=move |ev| {
+                    if let Ok(volume) = event_target_value(&ev).parse() {
+                        config.update(|c| c.alerts.alert_mut(sound).volume = volume);
+                    }
+                }
+                prop:value=move || config.get().alerts.alert(sound).volume.to_string()
+            />
+            <button
+                class="action-button"
+                title=move || t("Preview")
+                on:click=move |ev| {
+                    ev.prevent_default();
+                    sound::play(sound, config.get().alerts.alert(sound).volume);
+                }
+            >
+                "▶"
+            </button>
+        </label>
+    }
+}
+
+/// Settings for spoken announcements.
+#[component]
+fn SpeechInput(config: RwSignal<Config>) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    // Browsers load their voices lazily, so the list is refreshed whenever it is opened.
+    let voices = RwSignal::new(speech::voices());
+    let template = move |label: &'static str, field: fn(&mut SpeechSettings) -> &mut String| {
+        view! {
+            <label class="config-key-row">
+                <span class="field-label">{move || t(label)}</span>
+                <input
+                    type="text"
+                    class="config-text-input"
+                    title=move || t("{name} is replaced by the player, {time} by the time")
+                    on:input=move |ev| {
+                        let text = event_target_value(&ev);
+                        config.update(|c| *field(&mut c.speech) = text);
+                    }
+                    prop:value=move || field(&mut config.get().speech).clone()
+                />
+            </label>
+        }
+    };
+
+    view! {
+        <label class="config-label">
+            <input
+                type="checkbox"
+                on:change=move |ev| {
+                    let checked = event_target_checked(&ev);
+                    config.update(|c| c.speech.enabled = checked);
+                }
+                prop:checked=move || config.get().speech.enabled
+            />
+            " "
+            {move || t("Announce players")}
+        </label>
+        <label class="config-key-row">
+            <span class="field-label">{move || t("Voice")}</span>
+            <select
+                class="field-select"
+                on:focus=move |_| voices.set(speech::voices())
+                on:change=move |ev| {
+                    let voice = event_target_value(&ev);
+                    config.update(|c| c.speech.voice = voice);
+                }
+                prop:value=move || config.get().speech.voice
+            >
+                <option value="">{move || t("Default")}</option>
+                {move || {
+                    voices
+                        .get()
+                        .into_iter()
+                        .map(|voice| {
+                            let label = voice.clone();
+                            view! { <option value=voice>{label}</option> }
+                        })
+                        .collect_view()
+                }}
+            </select>
+        </label>
+        <label class="config-key-row">
+            <span class="field-label">{move || t("Rate")}</span>
+            <input
+                type="range"
+                min="50"
+                max="200"
+                class="config-volume"
+                on:input=move |ev| {
+                    if let Ok(rate) = event_target_value(&ev).parse() {
+                        config.update(|c| c.speech.rate = rate);
+                    }
+                }
+                prop:value=move || config.get().speech.rate.to_string()
+            />
+            <button
+                class="action-button"
+                title=move || t("Preview")
+                on:click=move |ev| {
+                    ev.prevent_default();
+                    let settings = config.get().speech;
+                    let name = t("Player {n}").replace("{n}", "1");
+                    let text = settings.turn_template.replace("{name}", &name);
+                    speech::speak(&text, &settings.voice, settings.rate);
+                }
+            >
+                "▶"
+            </button>
+        </label>
+        {template("Turn", |s| &mut s.turn_template)}
+        {template("Warning", |s| &mut s.warning_template)}
+        {template("Out of time", |s| &mut s.expiry_template)}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(names: &[&str]) -> Config {
+        let mut config = Config::for_test(names.len());
+        config.names = names.iter().map(|name| name.to_string()).collect();
+        config.handicaps = (0..names.len() as u32).map(|i| 100 + i).collect();
+        config.seat_buttons = (0..names.len())
+            .map(|seat| Some(SeatButton::Key(seat.to_string())))
+            .collect();
+        config
+    }
+
+    #[test]
+    fn moving_a_seat_shifts_the_players_in_between() {
+        let mut config = config(&["A", "B", "C", "D"]);
+        config.move_seat(0, 2);
+        assert_eq!(config.names, ["B", "C", "A", "D"]);
+        assert_eq!(config.handicaps, [101, 102, 100, 103]);
+        config.move_seat(3, 0);
+        assert_eq!(config.names, ["D", "B", "C", "A"]);
+    }
+
+    #[test]
+    fn the_first_player_moves_along() {
+        let mut config = config(&["A", "B", "C"]);
+        config.first_player = 1;
+        config.move_seat(1, 2);
+        assert_eq!(config.first_player, 2);
+        config.move_seat(0, 1);
+        assert_eq!(config.first_player, 2);
+    }
+
+    #[test]
+    fn seat_buttons_stay_with_the_seat() {
+        let mut config = config(&["A", "B", "C"]);
+        let buttons = config.seat_buttons.clone();
+        config.move_seat(0, 2);
+        assert_eq!(config.seat_buttons, buttons);
+    }
+
+    #[test]
+    fn moves_outside_the_table_are_ignored() {
+        let mut config = config(&["A", "B"]);
+        config.move_seat(0, 2);
+        config.move_seat(5, 0);
+        assert_eq!(config.names, ["A", "B"]);
+    }
+}
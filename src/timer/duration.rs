@@ -0,0 +1,136 @@
+/// How durations are written on clocks, in the table and in exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// `1:05:09`
+    HoursMinutesSeconds,
+    /// `65:09.3`
+    MinutesSecondsTenths,
+    /// `3909.3`
+    Seconds,
+    /// `1h 5m`, `5m 9s`, `9s`
+    Compact,
+}
+
+impl TimeFormat {
+    pub const ALL: [(&'static str, &'static str); 4] = [
+        ("hms", "H:MM:SS"),
+        ("mst", "MM:SS.t"),
+        ("s", "Seconds"),
+        ("compact", "Compact"),
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            TimeFormat::HoursMinutesSeconds => "hms",
+            TimeFormat::MinutesSecondsTenths => "mst",
+            TimeFormat::Seconds => "s",
+            TimeFormat::Compact => "compact",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        match key {
+            "hms" => TimeFormat::HoursMinutesSeconds,
+            "s" => TimeFormat::Seconds,
+            "compact" => TimeFormat::Compact,
+            _ => TimeFormat::MinutesSecondsTenths,
+        }
+    }
+
+    /// Formats `seconds`, with a leading minus sign for negative durations.
+    pub fn format(&self, seconds: f32) -> String {
+        let sign = if seconds < 0.0 { "-" } else { "" };
+        let tenths = (seconds.abs() * 10.0).round() as u64;
+        let secs = tenths / 10;
+        let (hours, minutes, rest) = (secs / 3600, secs / 60 % 60, secs % 60);
+        match self {
+            TimeFormat::HoursMinutesSeconds => {
+                format!("{}{}:{:02}:{:02}", sign, hours, minutes, rest)
+            }
+            TimeFormat::MinutesSecondsTenths => {
+                format!("{}{:02}:{:02}.{}", sign, secs / 60, rest, tenths % 10)
+            }
+            TimeFormat::Seconds => format!("{}{}.{}", sign, secs, tenths % 10),
+            TimeFormat::Compact if hours > 0 => format!("{}{}h {}m", sign, hours, minutes),
+            TimeFormat::Compact if minutes > 0 => format!("{}{}m {}s", sign, minutes, rest),
+            TimeFormat::Compact => format!("{}{}s", sign, rest),
+        }
+    }
+}
+
+/// Reads a duration typed as seconds (`90`, `90,5`) or with colons (`1:30`, `1:02:03`),
+/// optionally signed (`+2:00`, `-30`).
+pub fn parse_duration(text: &str) -> Option<f32> {
+    let text = text.trim().replace(',', ".");
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        let part = part.trim();
+        // Plain digits only, as `f32` would also read "inf", "NaN" and exponents.
+        let (whole, fraction) = part.split_once('.').unwrap_or((part, ""));
+        let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+            return None;
+        }
+        seconds = seconds * 60.0 + part.parse::<f32>().ok()?;
+    }
+    seconds.is_finite().then_some(sign * seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_each_style() {
+        let cases = [
+            (TimeFormat::HoursMinutesSeconds, "1:05:09"),
+            (TimeFormat::MinutesSecondsTenths, "65:09.3"),
+            (TimeFormat::Seconds, "3909.3"),
+            (TimeFormat::Compact, "1h 5m"),
+        ];
+        for (format, expected) in cases {
+            assert_eq!(format.format(3909.3), expected);
+        }
+        assert_eq!(TimeFormat::Compact.format(309.0), "5m 9s");
+        assert_eq!(TimeFormat::Compact.format(9.0), "9s");
+    }
+
+    #[test]
+    fn formats_negative_and_rounded_durations() {
+        assert_eq!(TimeFormat::MinutesSecondsTenths.format(-9.0), "-00:09.0");
+        assert_eq!(TimeFormat::Compact.format(-9.0), "-9s");
+        assert_eq!(TimeFormat::MinutesSecondsTenths.format(59.96), "01:00.0");
+        assert_eq!(TimeFormat::HoursMinutesSeconds.format(0.0), "0:00:00");
+    }
+
+    #[test]
+    fn parses_seconds_and_colons() {
+        assert_eq!(parse_duration("90"), Some(90.0));
+        assert_eq!(parse_duration("90,5"), Some(90.5));
+        assert_eq!(parse_duration(".5"), Some(0.5));
+        assert_eq!(parse_duration("1:30"), Some(90.0));
+        assert_eq!(parse_duration(" 1 : 30 "), Some(90.0));
+        assert_eq!(parse_duration("1:02:03"), Some(3723.0));
+        assert_eq!(parse_duration("+2:00"), Some(120.0));
+        assert_eq!(parse_duration("-30"), Some(-30.0));
+    }
+
+    #[test]
+    fn rejects_anything_but_plain_numbers() {
+        for text in [
+            "", "-", ".", ":", "1:", "1::2", "1..2", "1:-5", "--5", "+-5", "abc", "1:x", "inf",
+            "-inf", "infinity", "NaN", "nan", "1e9", "1E3", "0x10",
+        ] {
+            assert_eq!(parse_duration(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_durations_too_long_to_represent() {
+        assert_eq!(parse_duration(&"9".repeat(60)), None);
+    }
+}
@@ -0,0 +1,24 @@
+use super::native;
+use leptos::prelude::*;
+
+/// Largest clock that still fits `nplayers` panels side by side inside `panel`.
+pub fn clock_size(nplayers: usize, (_, _, width, height): (i32, i32, i32, i32)) -> i32 {
+    let cell = ((width * height) as f32 / nplayers.max(1) as f32).sqrt();
+    let largest = (width.min(height) / 3).max(100);
+    ((cell * 0.55) as i32).clamp(100, largest)
+}
+
+/// Puts the app into (or out of) fullscreen, through the window itself in the desktop build.
+pub fn set_fullscreen(fullscreen: bool) {
+    if native::invoke("set_fullscreen", &[("fullscreen", fullscreen.into())]) {
+        return;
+    }
+    let document = document();
+    if fullscreen {
+        if let Some(root) = document.document_element() {
+            let _ = root.request_fullscreen();
+        }
+    } else if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    }
+}
@@ -0,0 +1,82 @@
+/// Tracks the pointers (mouse, pen or fingers) held down on one player panel.
+///
+/// A single pointer that started on the drag handle moves the panel, a second pointer
+/// turns the gesture into a two-finger rotation.
+pub struct Gesture {
+    pointers: Vec<(i32, (i32, i32))>,
+    drag_offset: Option<(i32, i32)>,
+    rotate_from: Option<(f32, f32)>,
+}
+
+pub enum GestureUpdate {
+    Move(i32, i32),
+    Rotate(f32),
+}
+
+impl Gesture {
+    pub fn new() -> Self {
+        Self {
+            pointers: Vec::new(),
+            drag_offset: None,
+            rotate_from: None,
+        }
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        !self.pointers.is_empty()
+    }
+
+    /// Registers a new pointer. `drag_from` is the panel position when the pointer went
+    /// down on the drag handle, and `rotation` the panel's current rotation.
+    /// Returns false if the pointer is not part of the gesture.
+    pub fn down(
+        &mut self,
+        id: i32,
+        point: (i32, i32),
+        drag_from: Option<(i32, i32)>,
+        rotation: f32,
+    ) -> bool {
+        match self.pointers.len() {
+            0 => {
+                self.pointers.push((id, point));
+                self.drag_offset = drag_from.map(|(x, y)| (point.0 - x, point.1 - y));
+                true
+            }
+            1 => {
+                self.pointers.push((id, point));
+                self.drag_offset = None;
+                self.rotate_from = Some((self.angle(), rotation));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn moved(&mut self, id: i32, point: (i32, i32)) -> Option<GestureUpdate> {
+        let (_, tracked) = self.pointers.iter_mut().find(|(p, _)| *p == id)?;
+        *tracked = point;
+        if let Some((dx, dy)) = self.drag_offset {
+            Some(GestureUpdate::Move(point.0 - dx, point.1 - dy))
+        } else {
+            self.rotate_from
+                .map(|(angle, rotation)| GestureUpdate::Rotate(rotation + self.angle() - angle))
+        }
+    }
+
+    /// Removes a pointer. Lifting any finger ends the gesture for the remaining ones.
+    pub fn up(&mut self, id: i32) {
+        self.pointers.retain(|(p, _)| *p != id);
+        self.drag_offset = None;
+        self.rotate_from = None;
+    }
+
+    /// Angle in degrees of the line between the first two pointers.
+    fn angle(&self) -> f32 {
+        match self.pointers.as_slice() {
+            [(_, (x0, y0)), (_, (x1, y1)), ..] => {
+                ((y1 - y0) as f32).atan2((x1 - x0) as f32).to_degrees()
+            }
+            _ => 0.0,
+        }
+    }
+}
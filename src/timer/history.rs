@@ -0,0 +1,722 @@
+use super::duration::parse_duration;
+use super::pause::PauseReason;
+use super::{Config, Player};
+use leptos::{logging, prelude::*};
+
+/// One recorded turn of a player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Turn {
+    pub seconds: f32,
+    /// Changed by hand after it was recorded.
+    pub edited: bool,
+}
+
+impl Turn {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            seconds,
+            edited: false,
+        }
+    }
+
+    fn edited(seconds: f32) -> Self {
+        Self {
+            seconds,
+            edited: true,
+        }
+    }
+}
+
+/// What a manual time adjustment changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The time on the player's clock; added time counts as time used.
+    Clock,
+    /// The player's time bank; added time is more time to spend.
+    Bank,
+}
+
+/// Something that happened during a game outside of the normal flow of turns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Corrected {
+        seat: usize,
+        round: usize,
+        from: f32,
+        to: f32,
+    },
+    Split {
+        seat: usize,
+        round: usize,
+        other: usize,
+        seconds: f32,
+    },
+    Merged {
+        seat: usize,
+        round: usize,
+    },
+    Deleted {
+        seat: usize,
+        round: usize,
+        seconds: f32,
+    },
+    Inserted {
+        seat: usize,
+        round: usize,
+        seconds: f32,
+    },
+    Adjusted {
+        seat: usize,
+        target: Target,
+        seconds: f32,
+        reason: String,
+    },
+    /// The game was paused for `seconds` of wall-clock time.
+    Paused {
+        reason: PauseReason,
+        seconds: f32,
+    },
+}
+
+impl Event {
+    pub fn describe(&self, config: &Config) -> String {
+        let text = |template: &'static str| config.locale.text(template);
+        let fill = |template: &'static str, seat: usize, round: usize| {
+            text(template)
+                .replace("{name}", &config.player_name(seat))
+                .replace("{n}", &(round + 1).to_string())
+        };
+        match self {
+            &Event::Corrected {
+                seat,
+                round,
+                from,
+                to,
+            } => fill("{name}, round {n}: corrected {from} to {to}", seat, round)
+                .replace("{from}", &config.format_time(from))
+                .replace("{to}", &config.format_time(to)),
+            &Event::Split {
+                seat,
+                round,
+                other,
+                seconds,
+            } => fill("{name}, round {n}: moved {time} to {other}", seat, round)
+                .replace("{time}", &config.format_time(seconds))
+                .replace("{other}", &config.player_name(other)),
+            &Event::Merged { seat, round } => {
+                fill("{name}, round {n}: merged with the next turn", seat, round)
+            }
+            &Event::Deleted {
+                seat,
+                round,
+                seconds,
+            } => fill("{name}, round {n}: deleted turn of {time}", seat, round)
+                .replace("{time}", &config.format_time(seconds)),
+            &Event::Inserted {
+                seat,
+                round,
+                seconds,
+            } => fill("{name}, round {n}: inserted turn of {time}", seat, round)
+                .replace("{time}", &config.format_time(seconds)),
+            Event::Adjusted {
+                seat,
+                target,
+                seconds,
+                reason,
+            } => {
+                let template = match target {
+                    Target::Clock => "{name}: {time} on the clock",
+                    Target::Bank => "{name}: {time} on the bank",
+                };
+                let sign = if *seconds < 0.0 { "" } else { "+" };
+                let text = fill(template, *seat, 0).replace(
+                    "{time}",
+                    &format!("{}{}", sign, config.format_time(*seconds)),
+                );
+                if reason.trim().is_empty() {
+                    text
+                } else {
+                    format!("{} ({})", text, reason.trim())
+                }
+            }
+            &Event::Paused { reason, seconds } => {
+                let text =
+                    text("Paused for {time}").replace("{time}", &config.format_time(seconds));
+                if reason == PauseReason::Pause {
+                    text
+                } else {
+                    format!("{} ({})", text, config.locale.text(reason.label()))
+                }
+            }
+        }
+    }
+}
+
+/// A change to one seat's turns, given the seat and round. Returns the event to log,
+/// or `None` if the change does not apply.
+type Edit<'a> = dyn FnMut(usize, usize, &mut Vec<Turn>) -> Option<Event> + 'a;
+
+/// Sets the turn in `round` to `to` seconds.
+fn correct(turns: &mut [Turn], seat: usize, round: usize, to: f32) -> Option<Event> {
+    let turn = turns.get_mut(round).filter(|_| to >= 0.0)?;
+    let from = turn.seconds;
+    *turn = Turn::edited(to);
+    Some(Event::Corrected {
+        seat,
+        round,
+        from,
+        to,
+    })
+}
+
+/// Inserts a missing turn before `round`, or after the last turn if `round` is past it.
+fn insert(turns: &mut Vec<Turn>, seat: usize, round: usize, seconds: f32) -> Option<Event> {
+    if seconds < 0.0 {
+        return None;
+    }
+    let round = round.min(turns.len());
+    turns.insert(round, Turn::edited(seconds));
+    Some(Event::Inserted {
+        seat,
+        round,
+        seconds,
+    })
+}
+
+/// Adds the turn after `round` to it, for a turn that was passed on by mistake.
+fn merge(turns: &mut Vec<Turn>, seat: usize, round: usize) -> Option<Event> {
+    if round + 1 >= turns.len() {
+        return None;
+    }
+    let next = turns.remove(round + 1);
+    turns[round] = Turn::edited(turns[round].seconds + next.seconds);
+    Some(Event::Merged { seat, round })
+}
+
+/// Removes the turn in `round`, e.g. one that was recorded twice.
+fn delete(turns: &mut Vec<Turn>, seat: usize, round: usize) -> Option<Event> {
+    (round < turns.len()).then(|| Event::Deleted {
+        seat,
+        round,
+        seconds: turns.remove(round).seconds,
+    })
+}
+
+/// Moves `seconds` of the turn in `round` to `other`'s turn in the same round.
+///
+/// The other player usually took their turn in that round already. A player who is
+/// still on that round gets it as a new turn, but one further behind has no turn the
+/// time could belong to, so the split is refused.
+fn split(
+    turns: &mut [Turn],
+    other_turns: &mut Vec<Turn>,
+    seat: usize,
+    round: usize,
+    other: usize,
+    seconds: f32,
+) -> Option<Event> {
+    let turn = turns.get_mut(round)?;
+    if other == seat || seconds <= 0.0 || seconds >= turn.seconds || other_turns.len() < round {
+        return None;
+    }
+    *turn = Turn::edited(turn.seconds - seconds);
+    match other_turns.get_mut(round) {
+        Some(turn) => *turn = Turn::edited(turn.seconds + seconds),
+        None => other_turns.push(Turn::edited(seconds)),
+    }
+    Some(Event::Split {
+        seat,
+        round,
+        other,
+        seconds,
+    })
+}
+
+/// An [`Event`] with the game time it happened at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub at: f32,
+    pub event: Event,
+}
+
+/// Editor for the turn the user clicked in the time table.
+///
+/// `cell` is the selected seat and round; a round past the seat's last turn can only
+/// have a missing turn inserted.
+#[component]
+pub fn TurnEditor(
+    players: RwSignal<Vec<Player>>,
+    config: RwSignal<Config>,
+    log: RwSignal<Vec<LogEntry>>,
+    global_timer: ReadSignal<f32>,
+    cell: RwSignal<Option<(usize, usize)>>,
+) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    let duration = RwSignal::new(String::new());
+    let split_with = RwSignal::new(0);
+    let split_time = RwSignal::new(String::new());
+    let error = RwSignal::new(false);
+
+    let turns = move |seat: usize| players.with(|players| players.get(seat).map(|p| p.time));
+    // Prefill the inputs whenever another cell is selected.
+    Effect::new(move |_| {
+        if let Some((seat, round)) = cell.get() {
+            let seconds = turns(seat)
+                .and_then(|time| time.with_untracked(|turns| turns.get(round).copied()))
+                .map(|turn| turn.seconds)
+                .unwrap_or(0.0);
+            duration.set(config.with_untracked(|c| c.format_time(seconds)));
+            split_time.set(String::new());
+            split_with.set(if seat == 0 { 1 } else { 0 });
+            error.set(false);
+        }
+    });
+
+    // Runs `edit` on the selected seat's turns and logs the event it returns.
+    let apply = move |edit: &mut Edit<'_>| {
+        let Some((seat, round)) = cell.get_untracked() else {
+            return;
+        };
+        let Some(time) = turns(seat) else {
+            return;
+        };
+        let mut event = None;
+        time.update(|turns| event = edit(seat, round, turns));
+        match event {
+            Some(event) => {
+                logging::log!("{:?}", event);
+                error.set(false);
+                log.update(|log| {
+                    log.push(LogEntry {
+                        at: global_timer.get_untracked(),
+                        event,
+                    })
+                });
+            }
+            None => error.set(true),
+        }
+    };
+    let parsed = move |text: RwSignal<String>| text.with_untracked(|text| parse_duration(text));
+
+    let correct = move || {
+        let Some(to) = parsed(duration) else {
+            return error.set(true);
+        };
+        apply(&mut |seat, round, turns| correct(turns, seat, round, to));
+    };
+    let insert = move || {
+        let Some(seconds) = parsed(duration) else {
+            return error.set(true);
+        };
+        apply(&mut |seat, round, turns| insert(turns, seat, round, seconds));
+    };
+    let merge = move || apply(&mut |seat, round, turns| merge(turns, seat, round));
+    let delete = move || {
+        apply(&mut |seat, round, turns| delete(turns, seat, round));
+        cell.set(None);
+    };
+    let split = move || {
+        let other = split_with.get_untracked();
+        let (Some(seconds), Some(other_time)) = (parsed(split_time), turns(other)) else {
+            return error.set(true);
+        };
+        let mut other_turns = other_time.get_untracked();
+        let mut moved = false;
+        apply(&mut |seat, round, turns| {
+            let event = split(turns, &mut other_turns, seat, round, other, seconds);
+            moved = event.is_some();
+            event
+        });
+        if moved {
+            other_time.set(other_turns);
+        }
+    };
+
+    move || {
+        cell.get().map(|(seat, round)| {
+            let recorded = turns(seat).is_some_and(|time| time.with(|turns| round < turns.len()));
+            let title = config.with(|c| {
+                format!(
+                    "{} · {}",
+                    c.player_name(seat),
+                    c.locale
+                        .text("Round {n}")
+                        .replace("{n}", &(round + 1).to_string())
+                )
+            });
+            view! {
+                <div class="turn-editor">
+                    <div class="config-label">{title}</div>
+                    <div class="config-key-row">
+                        <input
+                            type="text"
+                            class="config-text-input"
+                            class:config-input-invalid=move || error.get()
+                            placeholder="1:30"
+                            on:input=move |ev| duration.set(event_target_value(&ev))
+                            prop:value=move || duration.get()
+                        />
+                        <Show when=move || recorded>
+                            <button class="action-button" on:click=move |_| correct()>
+                                {move || t("Correct")}
+                            </button>
+                        </Show>
+                        <button class="action-button" on:click=move |_| insert()>
+                            {move || t("Insert turn")}
+                        </button>
+                    </div>
+                    <Show when=move || recorded>
+                        <div class="config-key-row">
+                            <span class="field-label">{move || t("Move")}</span>
+                            <input
+                                type="text"
+                                class="config-text-input"
+                                placeholder="0:45"
+                                on:input=move |ev| split_time.set(event_target_value(&ev))
+                                prop:value=move || split_time.get()
+                            />
+                            <span class="field-label">{move || t("to")}</span>
+                            <select
+                                class="field-select"
+                                on:change=move |ev| {
+                                    if let Ok(other) = event_target_value(&ev).parse() {
+                                        split_with.set(other);
+                                    }
+                                }
+                                prop:value=move || split_with.get().to_string()
+                            >
+                                {move || {
+                                    let config = config.get();
+                                    (0..players.with(|players| players.len()))
+                                        .filter(|&other| other != seat)
+                                        .map(|other| {
+                                            view! {
+                                                <option value=other.to_string()>
+                                                    {config.player_name(other)}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()
+                                }}
+                            </select>
+                            <button class="action-button" on:click=move |_| split()>
+                                {move || t("Split")}
+                            </button>
+                        </div>
+                        <div class="config-key-row">
+                            <button class="action-button" on:click=move |_| merge()>
+                                {move || t("Merge with next")}
+                            </button>
+                            <button class="action-button" on:click=move |_| delete()>
+                                {move || t("Delete")}
+                            </button>
+                        </div>
+                    </Show>
+                    <button
+                        class="action-button turn-editor-close"
+                        title=move || t("Close")
+                        aria-label=move || t("Close")
+                        on:click=move |_| cell.set(None)
+                    >
+                        "×"
+                    </button>
+                </div>
+            }
+        })
+    }
+}
+
+/// Adds or takes away time from a player's clock or bank outside of the normal turns,
+/// e.g. a bonus or a penalty under house rules.
+#[component]
+pub fn AdjustTime(
+    players: RwSignal<Vec<Player>>,
+    config: RwSignal<Config>,
+    log: RwSignal<Vec<LogEntry>>,
+    global_timer: ReadSignal<f32>,
+    active_player: ReadSignal<usize>,
+) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    let seat = RwSignal::new(0);
+    let target = RwSignal::new(Target::Clock);
+    let amount = RwSignal::new(String::new());
+    let reason = RwSignal::new(String::new());
+    let error = RwSignal::new(false);
+    let has_banks = move || config.with(|c| c.time_bank > 0);
+
+    let adjust = move || {
+        let seat = seat.get_untracked();
+        let target = if has_banks() {
+            target.get_untracked()
+        } else {
+            Target::Clock
+        };
+        let seconds = amount.with_untracked(|amount| parse_duration(amount));
+        let player = players.with_untracked(|players| players.get(seat).cloned());
+        let (Some(seconds), Some(player)) = (seconds.filter(|&s| s != 0.0), player) else {
+            return error.set(true);
+        };
+        let applied = match target {
+            Target::Bank => {
+                player.bank_bonus.update(|bonus| *bonus += seconds);
+                seconds
+            }
+            // The running turn picks the change up through the clock's offset.
+            Target::Clock if active_player.get_untracked() == seat => {
+                player.clock.offset.update(|offset| *offset += seconds);
+                seconds
+            }
+            // A waiting clock shows the player's last turn, so that turn is changed.
+            Target::Clock => match player.time.with_untracked(|turns| turns.last().copied()) {
+                Some(last) => {
+                    let changed = (last.seconds + seconds).max(0.0);
+                    player.time.update(|turns| {
+                        if let Some(turn) = turns.last_mut() {
+                            *turn = Turn::edited(changed);
+                        }
+                    });
+                    player.clock.timer.set(changed);
+                    changed - last.seconds
+                }
+                // Before their first turn it is carried over into that turn instead.
+                None => {
+                    player.clock.offset.update(|offset| *offset += seconds);
+                    player.clock.timer.set(player.clock.offset.get_untracked());
+                    seconds
+                }
+            },
+        };
+        let event = Event::Adjusted {
+            seat,
+            target,
+            seconds: applied,
+            reason: reason.get_untracked(),
+        };
+        logging::log!("{:?}", event);
+        log.update(|log| {
+            log.push(LogEntry {
+                at: global_timer.get_untracked(),
+                event,
+            })
+        });
+        amount.set(String::new());
+        reason.set(String::new());
+        error.set(false);
+    };
+
+    view! {
+        <details class="adjust-time">
+            <summary>{move || t("Adjust time")}</summary>
+            <div class="config-key-row">
+                <select
+                    class="field-select"
+                    on:change=move |ev| {
+                        if let Ok(choice) = event_target_value(&ev).parse() {
+                            seat.set(choice);
+                        }
+                    }
+                    prop:value=move || seat.get().to_string()
+                >
+                    {move || {
+                        let config = config.get();
+                        (0..players.with(|players| players.len()))
+                            .map(|seat| {
+                                view! {
+                                    <option value=seat.to_string()>{config.player_name(seat)}</option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </select>
+                <Show when=has_banks>
+                    <select
+                        class="field-select"
+                        on:change=move |ev| {
+                            target
+                                .set(
+                                    if event_target_value(&ev) == "bank" {
+                                        Target::Bank
+                                    } else {
+                                        Target::Clock
+                                    },
+                                )
+                        }
+                        prop:value=move || {
+                            if target.get() == Target::Bank { "bank" } else { "clock" }
+                        }
+                    >
+                        <option value="clock">{move || t("Clock")}</option>
+                        <option value="bank">{move || t("Time bank")}</option>
+                    </select>
+                </Show>
+                <input
+                    type="text"
+                    class="config-text-input"
+                    class:config-input-invalid=move || error.get()
+                    placeholder="+2:00, -30"
+                    on:input=move |ev| amount.set(event_target_value(&ev))
+                    prop:value=move || amount.get()
+                />
+            </div>
+            <div class="config-key-row">
+                <input
+                    type="text"
+                    class="config-text-input"
+                    placeholder=move || t("Reason")
+                    on:input=move |ev| reason.set(event_target_value(&ev))
+                    prop:value=move || reason.get()
+                />
+                <button class="action-button" on:click=move |_| adjust()>
+                    {move || t("Apply")}
+                </button>
+            </div>
+        </details>
+    }
+}
+
+/// Hand-made changes and other notable events of the current game.
+#[component]
+pub fn GameLog(log: RwSignal<Vec<LogEntry>>, config: RwSignal<Config>) -> impl IntoView {
+    view! {
+        <Show when=move || log.with(|log| !log.is_empty())>
+            <details class="game-log">
+                <summary>{move || config.with(|c| c.locale.text("Game log"))}</summary>
+                <ul>
+                    {move || {
+                        let config = config.get();
+                        log.get()
+                            .into_iter()
+                            .map(|entry| {
+                                view! {
+                                    <li>
+                                        <span class="game-log-time">
+                                            {config.format_time(entry.at)}
+                                        </span>
+                                        {entry.event.describe(&config)}
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </ul>
+            </details>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turns(seconds: &[f32]) -> Vec<Turn> {
+        seconds.iter().copied().map(Turn::new).collect()
+    }
+
+    fn seconds(turns: &[Turn]) -> Vec<f32> {
+        turns.iter().map(|turn| turn.seconds).collect()
+    }
+
+    #[test]
+    fn corrects_recorded_turns_only() {
+        let mut time = turns(&[10.0, 20.0]);
+        assert_eq!(
+            correct(&mut time, 1, 1, 25.0),
+            Some(Event::Corrected {
+                seat: 1,
+                round: 1,
+                from: 20.0,
+                to: 25.0
+            })
+        );
+        assert_eq!(time[1], Turn::edited(25.0));
+        assert_eq!(correct(&mut time, 1, 2, 5.0), None);
+        assert_eq!(correct(&mut time, 1, 0, -5.0), None);
+        assert_eq!(seconds(&time), [10.0, 25.0]);
+    }
+
+    #[test]
+    fn inserts_before_the_round_or_at_the_end() {
+        let mut time = turns(&[10.0, 20.0]);
+        insert(&mut time, 0, 1, 5.0);
+        assert_eq!(seconds(&time), [10.0, 5.0, 20.0]);
+        assert_eq!(
+            insert(&mut time, 0, 7, 3.0),
+            Some(Event::Inserted {
+                seat: 0,
+                round: 3,
+                seconds: 3.0
+            })
+        );
+        assert_eq!(seconds(&time), [10.0, 5.0, 20.0, 3.0]);
+        assert_eq!(insert(&mut time, 0, 0, -1.0), None);
+    }
+
+    #[test]
+    fn merges_with_the_next_turn() {
+        let mut time = turns(&[10.0, 20.0, 30.0]);
+        assert_eq!(
+            merge(&mut time, 0, 0),
+            Some(Event::Merged { seat: 0, round: 0 })
+        );
+        assert_eq!(seconds(&time), [30.0, 30.0]);
+        assert!(time[0].edited);
+        assert_eq!(merge(&mut time, 0, 1), None);
+        assert_eq!(seconds(&time), [30.0, 30.0]);
+    }
+
+    #[test]
+    fn deletes_recorded_turns_only() {
+        let mut time = turns(&[10.0, 20.0]);
+        assert_eq!(
+            delete(&mut time, 0, 0),
+            Some(Event::Deleted {
+                seat: 0,
+                round: 0,
+                seconds: 10.0
+            })
+        );
+        assert_eq!(delete(&mut time, 0, 1), None);
+        assert_eq!(seconds(&time), [20.0]);
+    }
+
+    #[test]
+    fn splits_into_the_other_players_turn_of_the_round() {
+        let mut time = turns(&[10.0, 60.0]);
+        let mut other = turns(&[15.0, 20.0]);
+        assert_eq!(
+            split(&mut time, &mut other, 0, 1, 1, 25.0),
+            Some(Event::Split {
+                seat: 0,
+                round: 1,
+                other: 1,
+                seconds: 25.0
+            })
+        );
+        assert_eq!(seconds(&time), [10.0, 35.0]);
+        assert_eq!(seconds(&other), [15.0, 45.0]);
+        assert!(time[1].edited && other[1].edited);
+    }
+
+    #[test]
+    fn splits_into_a_new_turn_of_a_player_still_on_the_round() {
+        let mut time = turns(&[10.0, 60.0]);
+        let mut other = turns(&[15.0]);
+        split(&mut time, &mut other, 0, 1, 1, 25.0);
+        assert_eq!(seconds(&other), [15.0, 25.0]);
+    }
+
+    #[test]
+    fn refuses_splits_that_do_not_fit() {
+        let mut time = turns(&[10.0, 60.0, 30.0]);
+        let mut other = turns(&[15.0]);
+        // The other player has no turn in the third round yet.
+        assert_eq!(split(&mut time, &mut other, 0, 2, 1, 5.0), None);
+        // Nothing or the whole turn is not a split.
+        assert_eq!(split(&mut time, &mut other, 0, 0, 1, 0.0), None);
+        assert_eq!(split(&mut time, &mut other, 0, 0, 1, 10.0), None);
+        // Nor is a turn that was never recorded.
+        assert_eq!(split(&mut time, &mut other, 0, 3, 1, 5.0), None);
+        assert_eq!(seconds(&time), [10.0, 60.0, 30.0]);
+        assert_eq!(seconds(&other), [15.0]);
+    }
+}
@@ -0,0 +1,235 @@
+use leptos::prelude::window;
+
+/// Language of the user interface.
+///
+/// Messages are looked up by their English text, which doubles as the fallback for any
+/// message a catalog does not translate. Placeholders like `{name}` are filled in by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+    German,
+}
+
+impl Locale {
+    pub const ALL: [(&'static str, &'static str); 2] = [("en", "English"), ("de", "Deutsch")];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        match key {
+            "de" => Locale::German,
+            _ => Locale::English,
+        }
+    }
+
+    /// Locale matching the browser's preferred language, English if it is not supported.
+    pub fn detect() -> Self {
+        let language = window().navigator().language().unwrap_or_default();
+        Self::from_key(language.split('-').next().unwrap_or_default())
+    }
+
+    pub fn decimal_separator(&self) -> &'static str {
+        match self {
+            Locale::English => ".",
+            Locale::German => ",",
+        }
+    }
+
+    /// Translation of the English message `text`.
+    pub fn text(&self, text: &'static str) -> &'static str {
+        match self {
+            Locale::English => text,
+            Locale::German => german(text).unwrap_or(text),
+        }
+    }
+}
+
+fn german(text: &str) -> Option<&'static str> {
+    Some(match text {
+        // Configuration
+        "Language" => "Sprache",
+        "Number of Players" => "Anzahl Spieler",
+        "Number of Teams" => "Anzahl Teams",
+        "Alternate turns between teams" => "Teams abwechselnd ziehen lassen",
+        "Time bank (minutes, 0 = off)" => "Zeitkonto (Minuten, 0 = aus)",
+        "Pass-the-phone mode" => "Ein Gerät weiterreichen",
+        "Keep screen on during games" => "Bildschirm während des Spiels anlassen",
+        "Time format" => "Zeitformat",
+        "Theme" => "Design",
+        "Text size (%)" => "Schriftgröße (%)",
+        "Seating" => "Sitzordnung",
+        "Seats per side" => "Plätze pro Seite",
+        "Snap to grid (px)" => "Am Raster ausrichten (px)",
+        "Keep panels from overlapping" => "Überlappen verhindern",
+        "Table" => "Tisch",
+        "Default" => "Standard",
+        "Sounds" => "Töne",
+        "Turn change" => "Spielerwechsel",
+        "Warning" => "Warnung",
+        "Out of time" => "Zeit abgelaufen",
+        "Warn at (s)" => "Warnen bei (s)",
+        "Announcements" => "Ansagen",
+        "Announce players" => "Spieler ansagen",
+        "Voice" => "Stimme",
+        "Rate" => "Tempo",
+        "Turn" => "Zug",
+        "{name}, your turn" => "{name}, du bist dran",
+        "{name}, {time} left" => "{name}, noch {time}",
+        "{name}, time is up" => "{name}, deine Zeit ist um",
+        "{name} is replaced by the player, {time} by the time" => {
+            "{name} wird durch den Spieler ersetzt, {time} durch die Zeit"
+        }
+        "Preview" => "Vorhören",
+        "Keyboard shortcuts" => "Tastenkürzel",
+        "Press a key, Escape to cancel" => "Taste drücken, Escape bricht ab",
+        "End turn" => "Zug beenden",
+        "Pause" => "Pause",
+        "Back" => "Zurück",
+        "Help" => "Hilfe",
+        "Table mode" => "Tischmodus",
+        "Player color" => "Spielerfarbe",
+        "No team" => "Kein Team",
+        "Team {n}" => "Team {n}",
+        "Press to assign a physical button, Escape to cancel" => {
+            "Drücken, um eine Taste zuzuweisen, Escape bricht ab"
+        }
+        "Press a button…" => "Taste drücken…",
+        "Assign button" => "Taste zuweisen",
+        "Remove button" => "Taste entfernen",
+        "Key {key}" => "Taste {key}",
+        "Pad {pad} #{button}" => "Pad {pad} #{button}",
+        "Shared time bank" => "Gemeinsames Zeitkonto",
+        "Save" => "Speichern",
+        "Unsaved changes" => "Ungespeicherte Änderungen",
+        "Player {n}" => "Spieler {n}",
+        "Enter between {min} and {max} players" => "Zwischen {min} und {max} Spieler eingeben",
+        "Name already used" => "Name bereits vergeben",
+        "Time allowance (%)" => "Zeitzuschlag (%)",
+        "Handicaps from history" => "Zuschläge aus früheren Spielen",
+        "Slower players get more time, based on past games" => {
+            "Langsamere Spieler bekommen mehr Zeit, berechnet aus früheren Spielen"
+        }
+        "Drag to reorder" => "Zum Umsortieren ziehen",
+        "Move {name}, arrow keys reorder" => "{name} verschieben, Pfeiltasten sortieren um",
+        "First player" => "Startspieler",
+        "Randomize seating" => "Plätze auslosen",
+        "Pick first player" => "Startspieler auslosen",
+        "Seating preview" => "Vorschau der Sitzordnung",
+        "Seats are placed by hand" => "Plätze werden von Hand angeordnet",
+        "Settings" => "Einstellungen",
+        // Option labels
+        "Free placement" => "Freie Anordnung",
+        "Circle" => "Kreis",
+        "Rectangle table" => "Rechteckiger Tisch",
+        "Two facing sides" => "Zwei Seiten gegenüber",
+        "H:MM:SS" => "H:MM:SS",
+        "MM:SS.t" => "MM:SS,t",
+        "Seconds" => "Sekunden",
+        "Compact" => "Kompakt",
+        "Dark" => "Dunkel",
+        "Light" => "Hell",
+        "High contrast" => "Hoher Kontrast",
+        // Game controls
+        "Start" => "Start",
+        "Back to previous player" => "Zurück zum vorherigen Spieler",
+        "Reset game" => "Spiel zurücksetzen",
+        "Remember seating" => "Sitzordnung merken",
+        "Rotate player" => "Spieler drehen",
+        "Rotate {name}" => "{name} drehen",
+        "Drag to move" => "Zum Verschieben ziehen",
+        "movable panel" => "verschiebbares Feld",
+        "{name}, arrow keys move, R rotates" => "{name}, Pfeiltasten verschieben, R dreht",
+        "{name} {time}, end turn" => "{name} {time}, Zug beenden",
+        "Clock running" => "Uhr läuft",
+        "Paused" => "Pausiert",
+        "{name}'s turn" => "{name} ist am Zug",
+        "{name} is out of time" => "{name} hat keine Zeit mehr",
+        "Tap to end turn · swipe down to go back" => {
+            "Tippen beendet den Zug · nach unten wischen geht zurück"
+        }
+        "Rounds (0 = no limit)" => "Runden (0 = unbegrenzt)",
+        "Game time limit (minutes, 0 = off)" => "Spielzeitlimit (Minuten, 0 = aus)",
+        "Round {n} of {total}" => "Runde {n} von {total}",
+        "{time} left" => "noch {time}",
+        "Pause when idle (minutes, 0 = off)" => "Bei Inaktivität pausieren (Minuten, 0 = aus)",
+        "Rules question" => "Regelfrage",
+        "Food break" => "Essenspause",
+        "Bathroom break" => "Toilettenpause",
+        "No activity" => "Keine Aktivität",
+        "Break is over" => "Pause ist vorbei",
+        "Resume" => "Weiter",
+        // End of game
+        "Game over" => "Spielende",
+        "Turns" => "Züge",
+        "Average" => "Durchschnitt",
+        "Longest" => "Längster",
+        "Allowance" => "Zuschlag",
+        "Adjusted" => "Bereinigt",
+        "Archive" => "Archivieren",
+        "Archived" => "Archiviert",
+        "Export CSV" => "Als CSV exportieren",
+        "New game" => "Neues Spiel",
+        "Past games" => "Frühere Spiele",
+        "Played {play} · breaks {breaks} · total {total}" => {
+            "Gespielt {play} · Pausen {breaks} · gesamt {total}"
+        }
+        // Time table
+        "Keep score" => "Punkte zählen",
+        "Lowest score wins" => "Niedrigste Punktzahl gewinnt",
+        "Score" => "Punkte",
+        "Final" => "Endstand",
+        "Winner" => "Gewinner",
+        "Name" => "Name",
+        "Round {n}" => "Runde {n}",
+        "Total" => "Gesamt",
+        // Turn editor and game log
+        "Correct" => "Korrigieren",
+        "Insert turn" => "Zug einfügen",
+        "Move" => "Verschiebe",
+        "to" => "zu",
+        "Split" => "Aufteilen",
+        "Merge with next" => "Mit nächstem zusammenfassen",
+        "Delete" => "Löschen",
+        "Close" => "Schließen",
+        "Game log" => "Spielprotokoll",
+        "Adjust time" => "Zeit anpassen",
+        "Clock" => "Uhr",
+        "Time bank" => "Zeitkonto",
+        "Reason" => "Grund",
+        "Apply" => "Anwenden",
+        "{name}: {time} on the clock" => "{name}: {time} auf der Uhr",
+        "{name}: {time} on the bank" => "{name}: {time} auf dem Zeitkonto",
+        "{name}, round {n}: corrected {from} to {to}" => {
+            "{name}, Runde {n}: {from} auf {to} korrigiert"
+        }
+        "{name}, round {n}: moved {time} to {other}" => {
+            "{name}, Runde {n}: {time} an {other} übertragen"
+        }
+        "{name}, round {n}: merged with the next turn" => {
+            "{name}, Runde {n}: mit dem nächsten Zug zusammengefasst"
+        }
+        "{name}, round {n}: deleted turn of {time}" => {
+            "{name}, Runde {n}: Zug über {time} gelöscht"
+        }
+        "{name}, round {n}: inserted turn of {time}" => {
+            "{name}, Runde {n}: Zug über {time} eingefügt"
+        }
+        "Paused for {time}" => "{time} pausiert",
+        // Keyboard help
+        "Space" => "Leertaste",
+        "Backspace" => "Rücktaste",
+        "Escape" => "Esc",
+        "Arrows, R" => "Pfeiltasten, R",
+        "Pause / resume" => "Pause / weiter",
+        "Pass the turn to a seat" => "Zug an einen Platz übergeben",
+        "Move / rotate the focused name tag" => "Ausgewähltes Namensschild verschieben / drehen",
+        "Show / hide this help" => "Diese Hilfe ein- / ausblenden",
+        "Close this help" => "Diese Hilfe schließen",
+        _ => return None,
+    })
+}
@@ -0,0 +1,138 @@
+use super::i18n::Locale;
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    EndTurn,
+    Pause,
+    Back,
+    Help,
+    Fullscreen,
+    JumpTo(usize),
+}
+
+/// Keys bound to the game controls, stored as `KeyboardEvent.key` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    pub end_turn: String,
+    pub pause: String,
+    pub back: String,
+    pub help: String,
+    pub fullscreen: String,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        Self {
+            end_turn: " ".to_string(),
+            pause: "p".to_string(),
+            back: "Backspace".to_string(),
+            help: "?".to_string(),
+            fullscreen: "f".to_string(),
+        }
+    }
+
+    pub fn action_for(&self, key: &str) -> Option<Action> {
+        let matches = |binding: &str| binding.eq_ignore_ascii_case(key);
+        if matches(&self.end_turn) {
+            Some(Action::EndTurn)
+        } else if matches(&self.pause) {
+            Some(Action::Pause)
+        } else if matches(&self.back) {
+            Some(Action::Back)
+        } else if matches(&self.help) {
+            Some(Action::Help)
+        } else if matches(&self.fullscreen) {
+            Some(Action::Fullscreen)
+        } else {
+            // Number keys jump to a seat, with 0 standing in for the tenth player.
+            match key.parse::<usize>() {
+                Ok(0) => Some(Action::JumpTo(9)),
+                Ok(n) if n <= 9 => Some(Action::JumpTo(n - 1)),
+                _ => None,
+            }
+        }
+    }
+
+    /// Bindings paired with a description, in the order shown by the help overlay.
+    pub fn describe(&self, locale: Locale) -> Vec<(String, &'static str)> {
+        vec![
+            (key_label(&self.end_turn, locale), "End turn"),
+            (key_label(&self.pause, locale), "Pause / resume"),
+            (key_label(&self.back, locale), "Back to previous player"),
+            ("1 – 9, 0".to_string(), "Pass the turn to a seat"),
+            (key_label(&self.fullscreen, locale), "Table mode"),
+            (
+                locale.text("Arrows, R").to_string(),
+                "Move / rotate the focused name tag",
+            ),
+            (key_label(&self.help, locale), "Show / hide this help"),
+            (key_label("Escape", locale), "Close this help"),
+        ]
+    }
+}
+
+pub fn key_label(key: &str, locale: Locale) -> String {
+    match key {
+        " " => locale.text("Space").to_string(),
+        "Backspace" => locale.text("Backspace").to_string(),
+        "Escape" => locale.text("Escape").to_string(),
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key => key.to_string(),
+    }
+}
+
+/// Whether a key can be bound on its own. Tab still moves between fields, and a
+/// modifier alone is not a shortcut.
+pub fn is_bindable(key: &str) -> bool {
+    !matches!(
+        key,
+        "Tab" | "Shift" | "Control" | "Alt" | "AltGraph" | "Meta" | "CapsLock"
+    )
+}
+
+/// Whether a key event was typed into a form field and should not trigger shortcuts.
+pub fn is_typing(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA")
+        })
+}
+
+#[component]
+pub fn KeyHelp(
+    bindings: Signal<KeyBindings>,
+    locale: Signal<Locale>,
+    show: RwSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <Show when=move || show.get()>
+            <div class="dialog-overlay" on:click=move |_| show.set(false)>
+                <div class="dialog key-help" role="dialog" aria-modal="true" aria-label=move || locale.get().text("Keyboard shortcuts")>
+                    <div class="config-title">{move || locale.get().text("Keyboard shortcuts")}</div>
+                    <table class="time-table">
+                        <tbody>
+                            {move || {
+                                bindings
+                                    .get()
+                                    .describe(locale.get())
+                                    .into_iter()
+                                    .map(|(key, action)| {
+                                        view! {
+                                            <tr>
+                                                <td><kbd>{key}</kbd></td>
+                                                <td>{locale.get().text(action)}</td>
+                                            </tr>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </Show>
+    }
+}
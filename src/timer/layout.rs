@@ -0,0 +1,240 @@
+use super::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Room a player panel needs around its clock, used to keep seats fully on screen.
+const PANEL_PADDING: f32 = 10.0;
+const NAME_TAG_HEIGHT: f32 = 40.0;
+const MARGIN: f32 = 10.0;
+const SAVED_KEY: &str = "timer.seating";
+
+/// How player panels are arranged around the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Panels stay wherever they were dragged, or where they were last remembered.
+    Free,
+    Circle,
+    /// A rectangular table with `per_side` seats along each long side and the rest at the ends.
+    Rectangle {
+        per_side: usize,
+    },
+    FacingSides,
+}
+
+impl Layout {
+    pub const ALL: [(&'static str, &'static str); 4] = [
+        ("free", "Free placement"),
+        ("circle", "Circle"),
+        ("rectangle", "Rectangle table"),
+        ("facing", "Two facing sides"),
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Layout::Free => "free",
+            Layout::Circle => "circle",
+            Layout::Rectangle { .. } => "rectangle",
+            Layout::FacingSides => "facing",
+        }
+    }
+
+    pub fn from_key(key: &str, per_side: usize) -> Self {
+        match key {
+            "circle" => Layout::Circle,
+            "rectangle" => Layout::Rectangle { per_side },
+            "facing" => Layout::FacingSides,
+            _ => Layout::Free,
+        }
+    }
+}
+
+/// Width and height of a player panel whose clock is `clock` pixels across.
+pub fn panel_size(clock: i32) -> (f32, f32) {
+    (clock as f32 + PANEL_PADDING, clock as f32 + NAME_TAG_HEIGHT)
+}
+
+/// Top-left position and rotation of every seat for `layout` inside `panel` (x, y, width, height),
+/// for panels whose clock is `clock` pixels across.
+///
+/// Seats go clockwise starting at the bottom edge, and each panel is rotated so that its
+/// bottom faces the outside of the table, towards the player sitting there.
+/// Returns `None` for [`Layout::Free`], which uses the saved hand arrangement instead.
+pub fn seat_positions(
+    layout: Layout,
+    nplayers: usize,
+    (x, y, width, height): (i32, i32, i32, i32),
+    clock: i32,
+) -> Option<Vec<((i32, i32), f32)>> {
+    let (panel_width, panel_height) = panel_size(clock);
+    // Area available to the panel centres.
+    let left = x as f32 + panel_width / 2.0 + MARGIN;
+    let top = y as f32 + panel_height / 2.0 + MARGIN;
+    let right = (x + width) as f32 - panel_width / 2.0 - MARGIN;
+    let bottom = (y + height) as f32 - panel_height / 2.0 - MARGIN;
+
+    let centres = match layout {
+        Layout::Free => return None,
+        Layout::Circle => {
+            let (cx, cy) = ((left + right) / 2.0, (top + bottom) / 2.0);
+            let (rx, ry) = ((right - left).max(0.0) / 2.0, (bottom - top).max(0.0) / 2.0);
+            (0..nplayers)
+                .map(|i| {
+                    let angle = 90.0 + i as f32 * 360.0 / nplayers as f32;
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    ((cx + rx * cos, cy + ry * sin), angle - 90.0)
+                })
+                .collect()
+        }
+        Layout::Rectangle { per_side } => {
+            rectangle(nplayers, per_side.max(1), (left, top, right, bottom))
+        }
+        Layout::FacingSides => {
+            rectangle(nplayers, nplayers.div_ceil(2), (left, top, right, bottom))
+        }
+    };
+
+    Some(
+        centres
+            .into_iter()
+            .map(|((cx, cy), rotation)| {
+                let position = (
+                    (cx - panel_width / 2.0) as i32,
+                    (cy - panel_height / 2.0) as i32,
+                );
+                (position, rotation)
+            })
+            .collect(),
+    )
+}
+
+/// Seat centres along the edges of a rectangle: bottom, left end, top, right end.
+fn rectangle(
+    nplayers: usize,
+    per_side: usize,
+    (left, top, right, bottom): (f32, f32, f32, f32),
+) -> Vec<((f32, f32), f32)> {
+    let near = per_side.min(nplayers);
+    let far = per_side.min(nplayers - near);
+    let ends = nplayers - near - far;
+    let (left_end, right_end) = (ends.div_ceil(2), ends / 2);
+
+    // Fraction along a side for seat `k` of `count`, keeping seats off the corners.
+    let along = |k: usize, count: usize| (k as f32 + 0.5) / count as f32;
+    let lerp = |from: f32, to: f32, t: f32| from + (to - from) * t;
+
+    let mut seats = Vec::with_capacity(nplayers);
+    seats.extend((0..near).map(|k| ((lerp(right, left, along(k, near)), bottom), 0.0)));
+    seats.extend((0..left_end).map(|k| ((left, lerp(bottom, top, along(k, left_end))), 90.0)));
+    seats.extend((0..far).map(|k| ((lerp(left, right, along(k, far)), top), 180.0)));
+    seats.extend((0..right_end).map(|k| ((right, lerp(top, bottom, along(k, right_end))), 270.0)));
+    seats
+}
+
+/// A seat remembered relative to the timer panel, so it survives resizing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct SavedSeat {
+    x: f32,
+    y: f32,
+    rotation: f32,
+}
+
+/// Hand-arranged seatings, keyed by table name and player count.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedLayouts(HashMap<String, Vec<SavedSeat>>);
+
+impl SavedLayouts {
+    pub fn load() -> Self {
+        storage::load(SAVED_KEY).unwrap_or_default()
+    }
+
+    fn key(table: &str, nplayers: usize) -> String {
+        format!("{}#{}", table.trim(), nplayers)
+    }
+
+    /// Saves the current seats for `table` and persists all layouts.
+    pub fn remember(
+        &mut self,
+        table: &str,
+        seats: &[((i32, i32), f32)],
+        (x, y, width, height): (i32, i32, i32, i32),
+    ) {
+        let relative = seats
+            .iter()
+            .map(|&((px, py), rotation)| SavedSeat {
+                x: (px - x) as f32 / width.max(1) as f32,
+                y: (py - y) as f32 / height.max(1) as f32,
+                rotation,
+            })
+            .collect();
+        self.0.insert(Self::key(table, seats.len()), relative);
+        storage::save(SAVED_KEY, self);
+    }
+
+    /// Seats saved for `table` and `nplayers`, scaled to the current panel.
+    pub fn restore(
+        &self,
+        table: &str,
+        nplayers: usize,
+        (x, y, width, height): (i32, i32, i32, i32),
+    ) -> Option<Vec<((i32, i32), f32)>> {
+        let seats = self.0.get(&Self::key(table, nplayers))?;
+        Some(
+            seats
+                .iter()
+                .map(|seat| {
+                    let position = (
+                        x + (seat.x * width as f32) as i32,
+                        y + (seat.y * height as f32) as i32,
+                    );
+                    (position, seat.rotation)
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PANEL: (i32, i32, i32, i32) = (0, 0, 1000, 800);
+
+    fn inside(positions: &[((i32, i32), f32)]) -> bool {
+        let (width, height) = panel_size(100);
+        positions.iter().all(|&((x, y), _)| {
+            x >= 0
+                && y >= 0
+                && x as f32 + width <= PANEL.2 as f32
+                && y as f32 + height <= PANEL.3 as f32
+        })
+    }
+
+    #[test]
+    fn free_placement_has_no_seats() {
+        assert_eq!(seat_positions(Layout::Free, 4, PANEL, 100), None);
+    }
+
+    #[test]
+    fn circle_turns_each_seat_outwards() {
+        let seats = seat_positions(Layout::Circle, 4, PANEL, 100).unwrap();
+        let rotations: Vec<f32> = seats.iter().map(|&(_, rotation)| rotation).collect();
+        assert_eq!(rotations, vec![0.0, 90.0, 180.0, 270.0]);
+        assert!(inside(&seats));
+    }
+
+    #[test]
+    fn every_layout_seats_everyone_on_the_table() {
+        for layout in [
+            Layout::Circle,
+            Layout::Rectangle { per_side: 2 },
+            Layout::Rectangle { per_side: 0 },
+            Layout::FacingSides,
+        ] {
+            for nplayers in [0, 1, 2, 5, 9] {
+                let seats = seat_positions(layout, nplayers, PANEL, 100).unwrap();
+                assert_eq!(seats.len(), nplayers, "{:?}", layout);
+                assert!(inside(&seats), "{:?} with {}", layout, nplayers);
+            }
+        }
+    }
+}
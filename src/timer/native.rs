@@ -0,0 +1,31 @@
+use leptos::{logging, prelude::*};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::{Function, Object, Reflect};
+
+/// `window.__TAURI__.core.invoke`, only present when running inside the desktop build.
+fn tauri_invoke() -> Option<Function> {
+    let tauri = Reflect::get(&window(), &"__TAURI__".into()).ok()?;
+    let core = Reflect::get(&tauri, &"core".into()).ok()?;
+    Reflect::get(&core, &"invoke".into()).ok()?.dyn_into().ok()
+}
+
+pub fn is_desktop() -> bool {
+    tauri_invoke().is_some()
+}
+
+/// Runs a command of the desktop app with named arguments.
+///
+/// Returns false in the browser build, so callers can fall back to a web API.
+pub fn invoke(command: &str, args: &[(&str, JsValue)]) -> bool {
+    let Some(invoke) = tauri_invoke() else {
+        return false;
+    };
+    let payload = Object::new();
+    for (name, value) in args {
+        let _ = Reflect::set(&payload, &(*name).into(), value);
+    }
+    if let Err(err) = invoke.call2(&JsValue::NULL, &command.into(), &payload) {
+        logging::warn!("{} failed: {:?}", command, err);
+    }
+    true
+}
@@ -0,0 +1,127 @@
+use super::Config;
+use super::sound::Sound;
+use leptos::prelude::*;
+
+/// Why the game was paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    Pause,
+    Rules,
+    Food,
+    Bathroom,
+    /// Paused automatically after a while without any interaction.
+    Idle,
+}
+
+impl PauseReason {
+    /// Reasons that can be picked for a running pause.
+    pub const ALL: [PauseReason; 4] = [
+        PauseReason::Pause,
+        PauseReason::Rules,
+        PauseReason::Food,
+        PauseReason::Bathroom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseReason::Pause => "Pause",
+            PauseReason::Rules => "Rules question",
+            PauseReason::Food => "Food break",
+            PauseReason::Bathroom => "Bathroom break",
+            PauseReason::Idle => "No activity",
+        }
+    }
+
+    /// Planned length of the break in seconds, counted down while it lasts.
+    pub fn length(&self) -> Option<f32> {
+        match self {
+            PauseReason::Food => Some(15.0 * 60.0),
+            PauseReason::Bathroom => Some(5.0 * 60.0),
+            _ => None,
+        }
+    }
+}
+
+/// A pause in progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Break {
+    pub reason: PauseReason,
+    /// When the pause began or last changed its reason, in seconds of wall-clock time
+    /// since the page loaded.
+    pub since: f32,
+}
+
+/// Banner shown while the game is paused, with the break's reason and countdown.
+#[component]
+pub fn PauseBanner(
+    pause: RwSignal<Option<Break>>,
+    background_timer: RwSignal<f32>,
+    config: RwSignal<Config>,
+    change_reason: impl Fn(PauseReason) + Copy + Send + Sync + 'static,
+    resume: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    let elapsed = move || {
+        pause
+            .get()
+            .map(|pause| background_timer.get() - pause.since)
+            .unwrap_or(0.0)
+    };
+    // Seconds left of a break with a planned length.
+    let left = move || {
+        pause
+            .get()
+            .and_then(|pause| pause.reason.length())
+            .map(|length| length - elapsed())
+    };
+    Effect::new(move |was_over: Option<bool>| {
+        let over = left().is_some_and(|left| left <= 0.0);
+        if over && was_over == Some(false) {
+            config.with_untracked(|c| c.alerts.play(Sound::Warning));
+        }
+        over
+    });
+
+    view! {
+        <Show when=move || pause.get().is_some()>
+            <div class="pause-banner" class:pause-banner-over=move || left().is_some_and(|left| left <= 0.0)>
+                <span class="pause-banner-time">
+                    {move || {
+                        config
+                            .with(|c| match left() {
+                                Some(left) if left > 0.0 => {
+                                    c.locale.text("{time} left").replace("{time}", &c.format_time(left))
+                                }
+                                Some(_) => c.locale.text("Break is over").to_string(),
+                                None => c.format_time(elapsed()),
+                            })
+                    }}
+                </span>
+                {PauseReason::ALL
+                    .into_iter()
+                    .map(|reason| {
+                        let current = move || pause.get().is_some_and(|pause| pause.reason == reason);
+                        view! {
+                            <button
+                                class="pause-banner-reason"
+                                class:pause-banner-reason-active=current
+                                aria-pressed=move || current().to_string()
+                                on:click=move |_| change_reason(reason)
+                            >
+                                {move || t(reason.label())}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+                <Show when=move || pause.get().is_some_and(|pause| pause.reason == PauseReason::Idle)>
+                    <span class="pause-banner-reason pause-banner-reason-active">
+                        {move || t(PauseReason::Idle.label())}
+                    </span>
+                </Show>
+                <button class="action-button" on:click=move |_| resume()>
+                    {move || t("Resume")}
+                </button>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,71 @@
+use super::{BankTime, Config, Player};
+use leptos::{logging, prelude::*};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Vertical distance in pixels a pointer has to travel down to count as a swipe.
+const SWIPE_DISTANCE: i32 = 80;
+/// Movement below which a press still counts as a tap.
+const TAP_SLOP: i32 = 15;
+
+/// Full-screen clock of the active player for games played on a single phone.
+///
+/// Tapping anywhere ends the turn, swiping down goes back to the previous player.
+#[component]
+pub fn PhoneClock(
+    players: RwSignal<Vec<Player>>,
+    active_player: ReadSignal<usize>,
+    banks: Memo<Vec<Option<f32>>>,
+    panel_size: ReadSignal<(i32, i32, i32, i32)>,
+    config: RwSignal<Config>,
+    player_toggle: impl Fn() + 'static,
+    go_back: impl Fn() + 'static,
+) -> impl IntoView {
+    let active = move || players.get().get(active_player.get()).cloned();
+    let start = Rc::new(Cell::new(None));
+
+    let on_pointer_down = {
+        let start = Rc::clone(&start);
+        move |ev: web_sys::PointerEvent| start.set(Some((ev.client_x(), ev.client_y())))
+    };
+    let on_pointer_up = move |ev: web_sys::PointerEvent| {
+        let Some((x, y)) = start.take() else {
+            return;
+        };
+        let (dx, dy) = (ev.client_x() - x, ev.client_y() - y);
+        if dy > SWIPE_DISTANCE && dy > dx.abs() {
+            logging::log!("swipe down, going back");
+            go_back();
+        } else if dx.abs() < TAP_SLOP && dy.abs() < TAP_SLOP {
+            player_toggle();
+        }
+    };
+
+    view! {
+        <div
+            class="phone-clock"
+            // Cover the timer panel only, leaving the configuration reachable.
+            style:top=move || format!("{}px", panel_size.get().1)
+            on:pointerdown=on_pointer_down
+            on:pointerup=on_pointer_up
+        >
+            {move || {
+                active()
+                    .map(|player| {
+                        view! {
+                            <div class="phone-clock-name">{player.name}</div>
+                            <div class="phone-clock-time">
+                                {move || {
+                                    config.with(|c| c.format_time(player.clock.timer.get()))
+                                }}
+                            </div>
+                            <BankTime banks seat=player.id config />
+                            <div class="phone-clock-hint">
+                                {config.with(|c| c.locale.text("Tap to end turn · swipe down to go back"))}
+                            </div>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
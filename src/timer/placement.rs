@@ -0,0 +1,83 @@
+/// Axis-aligned rectangle as (x, y, width, height).
+pub type Rect = (i32, i32, i32, i32);
+
+pub fn snap_to_grid((x, y): (i32, i32), grid: u32) -> (i32, i32) {
+    if grid == 0 {
+        return (x, y);
+    }
+    let grid = grid as f32;
+    let snap = |v: i32| ((v as f32 / grid).round() * grid) as i32;
+    (snap(x), snap(y))
+}
+
+pub fn overlaps((ax, ay, aw, ah): Rect, (bx, by, bw, bh): Rect) -> bool {
+    ax < bx + bw && bx < ax + aw && ay < by + bh && by < ay + ah
+}
+
+/// Moves `rect` out of the obstacles it overlaps, each time along the shortest way out.
+///
+/// Returns the freed position, or `None` if the rectangle is squeezed in too tightly to
+/// escape within a few steps.
+pub fn push_out(rect: Rect, obstacles: &[Rect]) -> Option<(i32, i32)> {
+    let (mut x, mut y, width, height) = rect;
+    for _ in 0..8 {
+        let Some(&(ox, oy, ow, oh)) = obstacles
+            .iter()
+            .find(|&&obstacle| overlaps((x, y, width, height), obstacle))
+        else {
+            return Some((x, y));
+        };
+        let left = x + width - ox;
+        let right = ox + ow - x;
+        let up = y + height - oy;
+        let down = oy + oh - y;
+        let shortest = left.min(right).min(up).min(down);
+        if shortest == left {
+            x -= left;
+        } else if shortest == right {
+            x += right;
+        } else if shortest == up {
+            y -= up;
+        } else {
+            y += down;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_rectangles_stay_put() {
+        assert_eq!(push_out((0, 0, 10, 10), &[(20, 0, 10, 10)]), Some((0, 0)));
+        assert_eq!(push_out((5, 5, 10, 10), &[]), Some((5, 5)));
+    }
+
+    #[test]
+    fn touching_edges_do_not_overlap() {
+        assert!(!overlaps((0, 0, 10, 10), (10, 0, 10, 10)));
+        assert!(overlaps((0, 0, 10, 10), (9, 9, 10, 10)));
+    }
+
+    #[test]
+    fn pushes_out_the_shortest_way() {
+        // Overlapping the obstacle's left edge by 3 pixels.
+        assert_eq!(push_out((0, 0, 10, 10), &[(7, -20, 50, 50)]), Some((-3, 0)));
+        // Overlapping its bottom edge by 2 pixels.
+        assert_eq!(
+            push_out((0, 28, 10, 10), &[(-20, 0, 50, 30)]),
+            Some((0, 30))
+        );
+    }
+
+    #[test]
+    fn ends_clear_of_every_obstacle() {
+        let obstacles = [(10, 0, 10, 10), (0, 10, 10, 10)];
+        let (x, y) = push_out((5, 5, 10, 10), &obstacles).expect("room to move");
+        for obstacle in obstacles {
+            assert!(!overlaps((x, y, 10, 10), obstacle));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/// Seats with the best score, more than one on a tie. Seats without a score never win,
+/// so the list stays empty until someone has scored.
+pub fn winners(scores: &[Option<i32>], lowest_wins: bool) -> Vec<usize> {
+    let best = scores.iter().flatten().copied().reduce(|best, score| {
+        if lowest_wins {
+            best.min(score)
+        } else {
+            best.max(score)
+        }
+    });
+    let Some(best) = best else {
+        return Vec::new();
+    };
+    scores
+        .iter()
+        .enumerate()
+        .filter(|&(_, &score)| score == Some(best))
+        .map(|(seat, _)| seat)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nobody_wins_without_scores() {
+        assert!(winners(&[], false).is_empty());
+        assert!(winners(&[None, None], false).is_empty());
+    }
+
+    #[test]
+    fn highest_or_lowest_score_wins() {
+        let scores = [Some(3), None, Some(7), Some(-2)];
+        assert_eq!(winners(&scores, false), vec![2]);
+        assert_eq!(winners(&scores, true), vec![3]);
+    }
+
+    #[test]
+    fn ties_share_the_win() {
+        assert_eq!(winners(&[Some(5), Some(2), Some(5)], false), vec![0, 2]);
+    }
+}
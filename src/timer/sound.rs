@@ -0,0 +1,139 @@
+use leptos::logging;
+use std::cell::RefCell;
+use web_sys::{AudioContext, AudioContextState, OscillatorType};
+
+thread_local! {
+    static AUDIO: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    TurnChange,
+    Warning,
+    Expiry,
+}
+
+impl Sound {
+    /// Notes of the sound as (frequency in Hz, start and length in seconds).
+    fn notes(&self) -> &'static [(f32, f64, f64)] {
+        match self {
+            Sound::TurnChange => &[(880.0, 0.0, 0.12), (1320.0, 0.12, 0.2)],
+            Sound::Warning => &[(660.0, 0.0, 0.15), (660.0, 0.25, 0.15)],
+            Sound::Expiry => &[(440.0, 0.0, 0.3), (330.0, 0.3, 0.3), (220.0, 0.6, 0.6)],
+        }
+    }
+}
+
+/// An alert that can be switched on or off, with its volume in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alert {
+    pub enabled: bool,
+    pub volume: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertSettings {
+    pub turn_change: Alert,
+    pub warning: Alert,
+    pub expiry: Alert,
+    /// Seconds at which the warning sounds: remaining bank time when a bank is set,
+    /// otherwise time elapsed in the turn.
+    pub warning_at: Vec<u32>,
+}
+
+impl AlertSettings {
+    pub fn new() -> Self {
+        Self {
+            turn_change: Alert {
+                enabled: true,
+                volume: 50,
+            },
+            warning: Alert {
+                enabled: true,
+                volume: 60,
+            },
+            expiry: Alert {
+                enabled: true,
+                volume: 80,
+            },
+            warning_at: vec![60, 10],
+        }
+    }
+
+    pub fn alert(&self, sound: Sound) -> Alert {
+        match sound {
+            Sound::TurnChange => self.turn_change,
+            Sound::Warning => self.warning,
+            Sound::Expiry => self.expiry,
+        }
+    }
+
+    pub fn alert_mut(&mut self, sound: Sound) -> &mut Alert {
+        match sound {
+            Sound::TurnChange => &mut self.turn_change,
+            Sound::Warning => &mut self.warning,
+            Sound::Expiry => &mut self.expiry,
+        }
+    }
+
+    /// Plays `sound` if its alert is enabled.
+    pub fn play(&self, sound: Sound) {
+        let alert = self.alert(sound);
+        if alert.enabled && alert.volume > 0 {
+            play(sound, alert.volume);
+        }
+    }
+}
+
+/// Synthesizes `sound` with the Web Audio API at `volume` percent.
+pub fn play(sound: Sound, volume: u8) {
+    AUDIO.with_borrow_mut(|audio| {
+        if audio.is_none() {
+            *audio = AudioContext::new()
+                .inspect_err(|err| logging::warn!("audio unavailable: {:?}", err))
+                .ok();
+        }
+        let Some(context) = audio else {
+            return;
+        };
+        // Browsers start the context suspended until the page has been interacted with.
+        if context.state() == AudioContextState::Suspended {
+            let _ = context.resume();
+        }
+        let gain = volume.min(100) as f32 / 100.0;
+        let now = context.current_time();
+        for &(frequency, start, length) in sound.notes() {
+            if let Err(err) = note(context, frequency, now + start, length, gain) {
+                logging::warn!("failed to play {:?}: {:?}", sound, err);
+            }
+        }
+    });
+}
+
+fn note(
+    context: &AudioContext,
+    frequency: f32,
+    start: f64,
+    length: f64,
+    gain: f32,
+) -> Result<(), wasm_bindgen::JsValue> {
+    let oscillator = context.create_oscillator()?;
+    oscillator.set_type(OscillatorType::Sine);
+    oscillator.frequency().set_value_at_time(frequency, start)?;
+
+    // Short fade in and out so notes don't click.
+    let envelope = context.create_gain()?;
+    envelope.gain().set_value_at_time(0.0, start)?;
+    envelope
+        .gain()
+        .linear_ramp_to_value_at_time(gain, start + 0.01)?;
+    envelope
+        .gain()
+        .linear_ramp_to_value_at_time(0.0, start + length)?;
+
+    oscillator.connect_with_audio_node(&envelope)?;
+    envelope.connect_with_audio_node(&context.destination())?;
+    oscillator.start_with_when(start)?;
+    oscillator.stop_with_when(start + length)?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use super::i18n::Locale;
+use super::native;
+use leptos::{logging, prelude::*};
+use wasm_bindgen::JsCast;
+use web_sys::js_sys::Reflect;
+use web_sys::{SpeechSynthesis, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+/// Spoken announcements. Templates may use `{name}` for the player and `{time}` for the
+/// time left or spent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    /// Name of the browser voice, empty for the default voice.
+    pub voice: String,
+    /// Speaking rate in percent of normal speed.
+    pub rate: u32,
+    pub turn_template: String,
+    pub warning_template: String,
+    pub expiry_template: String,
+}
+
+const TURN_TEMPLATE: &str = "{name}, your turn";
+const WARNING_TEMPLATE: &str = "{name}, {time} left";
+const EXPIRY_TEMPLATE: &str = "{name}, time is up";
+
+impl SpeechSettings {
+    pub fn new(locale: Locale) -> Self {
+        Self {
+            enabled: false,
+            voice: String::new(),
+            rate: 100,
+            turn_template: locale.text(TURN_TEMPLATE).to_string(),
+            warning_template: locale.text(WARNING_TEMPLATE).to_string(),
+            expiry_template: locale.text(EXPIRY_TEMPLATE).to_string(),
+        }
+    }
+
+    /// Translates the templates that were left at their default from `from` into `to`.
+    pub fn translate_defaults(&mut self, from: Locale, to: Locale) {
+        for (template, default) in [
+            (&mut self.turn_template, TURN_TEMPLATE),
+            (&mut self.warning_template, WARNING_TEMPLATE),
+            (&mut self.expiry_template, EXPIRY_TEMPLATE),
+        ] {
+            if *template == from.text(default) {
+                *template = to.text(default).to_string();
+            }
+        }
+    }
+
+    /// Speaks `template` with the placeholders filled in, if announcements are enabled.
+    pub fn announce(&self, locale: Locale, template: &str, name: &str, seconds: f32) {
+        if self.enabled {
+            let text = template
+                .replace("{name}", name)
+                .replace("{time}", &spoken_duration(locale, seconds));
+            speak(&text, &self.voice, self.rate);
+        }
+    }
+}
+
+/// Duration in words a speech engine reads naturally, e.g. "1 minute 30 seconds".
+pub fn spoken_duration(locale: Locale, seconds: f32) -> String {
+    let secs = seconds.max(0.0).round() as u64;
+    let (minute, second) = match locale {
+        Locale::English => (("minute", "minutes"), ("second", "seconds")),
+        Locale::German => (("Minute", "Minuten"), ("Sekunde", "Sekunden")),
+    };
+    let unit = |n: u64, (one, many): (&str, &str)| {
+        if n == 1 {
+            format!("1 {}", one)
+        } else {
+            format!("{} {}", n, many)
+        }
+    };
+    match (secs / 60, secs % 60) {
+        (0, s) => unit(s, second),
+        (m, 0) => unit(m, minute),
+        (m, s) => format!("{} {}", unit(m, minute), unit(s, second)),
+    }
+}
+
+fn synthesis() -> Option<SpeechSynthesis> {
+    // Some desktop webviews ship without the Web Speech API.
+    if !Reflect::has(&window(), &"speechSynthesis".into()).unwrap_or(false) {
+        return None;
+    }
+    window().speech_synthesis().ok()
+}
+
+/// Names of the voices the browser offers.
+pub fn voices() -> Vec<String> {
+    synthesis()
+        .map(|synthesis| {
+            synthesis
+                .get_voices()
+                .iter()
+                .filter_map(|voice| voice.dyn_into::<SpeechSynthesisVoice>().ok())
+                .map(|voice| voice.name())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Speaks `text`, interrupting anything still being said. Falls back to the desktop app's
+/// text-to-speech when the webview has no speech synthesis.
+pub fn speak(text: &str, voice: &str, rate: u32) {
+    let rate = rate.clamp(10, 400) as f32 / 100.0;
+    let Some(synthesis) = synthesis() else {
+        if !native::invoke("speak", &[("text", text.into()), ("rate", rate.into())]) {
+            logging::warn!("speech synthesis unavailable");
+        }
+        return;
+    };
+    let Ok(utterance) = SpeechSynthesisUtterance::new_with_text(text) else {
+        return;
+    };
+    utterance.set_rate(rate);
+    let voice = synthesis
+        .get_voices()
+        .iter()
+        .filter_map(|v| v.dyn_into::<SpeechSynthesisVoice>().ok())
+        .find(|v| v.name() == voice);
+    utterance.set_voice(voice.as_ref());
+    synthesis.cancel();
+    synthesis.speak(&utterance);
+}
@@ -0,0 +1,27 @@
+use leptos::{logging, prelude::*};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Reads a value saved with [`save`], if the browser has one stored under `key`.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(key).ok().flatten()?;
+    serde_json::from_str(&json)
+        .inspect_err(|err| logging::warn!("ignoring stored {}: {}", key, err))
+        .ok()
+}
+
+/// Stores `value` in the browser's local storage under `key`.
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    let Some(storage) = window().local_storage().ok().flatten() else {
+        logging::warn!("local storage unavailable, {} not saved", key);
+        return;
+    };
+    match serde_json::to_string(value) {
+        Ok(json) => {
+            if storage.set_item(key, &json).is_err() {
+                logging::warn!("failed to store {}", key);
+            }
+        }
+        Err(err) => logging::warn!("failed to serialize {}: {}", key, err),
+    }
+}
@@ -0,0 +1,390 @@
+use super::history::{Event, LogEntry};
+use super::score::winners;
+use super::{Config, Player, storage};
+use leptos::{logging, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::{Array, Date};
+
+const ARCHIVE_KEY: &str = "timer.archive";
+
+/// Time statistics of one player over a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub name: String,
+    pub turns: usize,
+    pub total: f32,
+    pub average: f32,
+    pub longest: f32,
+    pub score: Option<i32>,
+    pub winner: bool,
+    /// Time allowance as a factor.
+    pub handicap: f32,
+    /// Total time relative to the allowance, comparable between handicapped players.
+    pub adjusted: f32,
+}
+
+impl PlayerStats {
+    fn new(player: &ArchivedPlayer) -> Self {
+        let turns = &player.turns;
+        let total: f32 = turns.iter().sum();
+        Self {
+            name: player.name.clone(),
+            turns: turns.len(),
+            total,
+            average: if turns.is_empty() {
+                0.0
+            } else {
+                total / turns.len() as f32
+            },
+            longest: turns.iter().copied().fold(0.0, f32::max),
+            score: player.score,
+            winner: player.winner,
+            handicap: player.handicap,
+            adjusted: total / player.handicap.max(0.01),
+        }
+    }
+}
+
+/// A finished game as kept in the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedGame {
+    /// When the game ended, as an ISO 8601 timestamp.
+    pub date: String,
+    pub table: String,
+    pub players: Vec<ArchivedPlayer>,
+    /// The game log, as it was displayed.
+    pub log: Vec<String>,
+    /// Seconds spent on turns.
+    #[serde(default)]
+    pub play_time: f32,
+    /// Seconds the game was paused.
+    #[serde(default)]
+    pub break_time: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedPlayer {
+    pub name: String,
+    pub turns: Vec<f32>,
+    #[serde(default)]
+    pub score: Option<i32>,
+    #[serde(default)]
+    pub winner: bool,
+    #[serde(default = "no_handicap")]
+    pub handicap: f32,
+}
+
+fn no_handicap() -> f32 {
+    1.0
+}
+
+impl ArchivedGame {
+    fn new(players: &[Player], log: &[LogEntry], config: &Config) -> Self {
+        let scores: Vec<_> = players
+            .iter()
+            .map(|player| untrack(|| player.score()))
+            .collect();
+        let winners = winners(&scores, config.lowest_wins);
+        let play_time = players
+            .iter()
+            .map(|player| {
+                player
+                    .time
+                    .with_untracked(|turns| turns.iter().map(|turn| turn.seconds).sum::<f32>())
+            })
+            .sum();
+        let break_time = log
+            .iter()
+            .map(|entry| match entry.event {
+                Event::Paused { seconds, .. } => seconds,
+                _ => 0.0,
+            })
+            .sum();
+        Self {
+            date: Date::new_0().to_iso_string().into(),
+            table: config.table.clone(),
+            players: players
+                .iter()
+                .map(|player| ArchivedPlayer {
+                    name: player.name.get_untracked(),
+                    turns: player
+                        .time
+                        .with_untracked(|turns| turns.iter().map(|turn| turn.seconds).collect()),
+                    score: scores[player.id],
+                    winner: winners.contains(&player.id),
+                    handicap: config.handicap(player.id),
+                })
+                .collect(),
+            log: log
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{} {}",
+                        config.format_time(entry.at),
+                        entry.event.describe(config)
+                    )
+                })
+                .collect(),
+            play_time,
+            break_time,
+        }
+    }
+
+    pub fn stats(&self) -> Vec<PlayerStats> {
+        self.players.iter().map(PlayerStats::new).collect()
+    }
+
+    /// Turn times as CSV, one row per player with the times in seconds.
+    fn to_csv(&self) -> String {
+        let rounds = self
+            .players
+            .iter()
+            .map(|player| player.turns.len())
+            .max()
+            .unwrap_or(0);
+        let mut csv = String::from("Name");
+        for round in 1..=rounds {
+            csv.push_str(&format!(",Round {}", round));
+        }
+        csv.push_str(",Total,Allowance,Score,Winner\n");
+        for player in &self.players {
+            csv.push_str(&format!("\"{}\"", player.name.replace('"', "\"\"")));
+            for round in 0..rounds {
+                csv.push(',');
+                if let Some(seconds) = player.turns.get(round) {
+                    csv.push_str(&format!("{:.1}", seconds));
+                }
+            }
+            csv.push_str(&format!(
+                ",{:.1},{:.0}%,{},{}\n",
+                player.turns.iter().sum::<f32>(),
+                player.handicap * 100.0,
+                player
+                    .score
+                    .map(|score| score.to_string())
+                    .unwrap_or_default(),
+                if player.winner { "yes" } else { "" },
+            ));
+        }
+        csv
+    }
+}
+
+/// Finished games saved in the browser, newest last.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Archive(Vec<ArchivedGame>);
+
+impl Archive {
+    pub fn load() -> Self {
+        storage::load(ARCHIVE_KEY).unwrap_or_default()
+    }
+
+    pub fn add(&mut self, game: ArchivedGame) {
+        self.0.push(game);
+        storage::save(ARCHIVE_KEY, self);
+    }
+
+    pub fn games(&self) -> &[ArchivedGame] {
+        &self.0
+    }
+
+    /// Average turn time of every player in the archive, by name.
+    pub fn average_turns(&self) -> HashMap<String, f32> {
+        let mut turns: HashMap<String, (f32, usize)> = HashMap::new();
+        for player in self.0.iter().flat_map(|game| &game.players) {
+            let (total, count) = turns.entry(player.name.clone()).or_default();
+            *total += player.turns.iter().sum::<f32>();
+            *count += player.turns.len();
+        }
+        turns
+            .into_iter()
+            .filter(|&(_, (_, count))| count > 0)
+            .map(|(name, (total, count))| (name, total / count as f32))
+            .collect()
+    }
+}
+
+/// Offers `content` as a file download.
+fn download(filename: &str, mime: &str, content: &str) -> Result<(), JsValue> {
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob =
+        web_sys::Blob::new_with_str_sequence_and_options(&Array::of1(&content.into()), &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let link: web_sys::HtmlAnchorElement = document().create_element("a")?.unchecked_into();
+    link.set_href(&url);
+    link.set_download(filename);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Statistics shown when the game has ended, with the option to archive or export it.
+#[component]
+pub fn Summary(
+    players: RwSignal<Vec<Player>>,
+    config: RwSignal<Config>,
+    log: RwSignal<Vec<LogEntry>>,
+    show: RwSignal<bool>,
+    new_game: impl Fn() + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let t = move |text: &'static str| config.with(|c| c.locale.text(text));
+    let archive = RwSignal::new(Archive::load());
+    let archived = RwSignal::new(false);
+    // A snapshot of the game, taken when the summary opens.
+    let game = RwSignal::new(None::<ArchivedGame>);
+    Effect::new(move |_| {
+        if show.get() {
+            let snapshot = config.with_untracked(|c| {
+                ArchivedGame::new(&players.get_untracked(), &log.get_untracked(), c)
+            });
+            // Reopening the summary of the same game keeps it marked as archived.
+            let changed = game.with_untracked(|game| {
+                game.as_ref()
+                    .is_none_or(|game| game.players != snapshot.players)
+            });
+            if changed {
+                archived.set(false);
+            }
+            game.set(Some(snapshot));
+        }
+    });
+
+    let save = move || {
+        if let Some(game) = game.get_untracked() {
+            archive.update(|archive| archive.add(game));
+            archived.set(true);
+        }
+    };
+    let export = move || {
+        if let Some(game) = game.get_untracked() {
+            let name = format!("game-{}.csv", game.date.get(..10).unwrap_or("export"));
+            if let Err(err) = download(&name, "text/csv", &game.to_csv()) {
+                logging::warn!("export failed: {:?}", err);
+            }
+        }
+    };
+
+    view! {
+        <Show when=move || show.get()>
+            <div class="dialog-overlay">
+                <div class="dialog game-summary" role="dialog" aria-modal="true">
+                    <div class="config-title">{move || t("Game over")}</div>
+                    <table class="time-table">
+                        <thead>
+                            <tr>
+                                <th>{move || t("Name")}</th>
+                                <th>{move || t("Turns")}</th>
+                                <th>{move || t("Total")}</th>
+                                <th>{move || t("Average")}</th>
+                                <th>{move || t("Longest")}</th>
+                                <th>{move || t("Allowance")}</th>
+                                <th>{move || t("Adjusted")}</th>
+                                <th>{move || t("Score")}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || {
+                                let config = config.get();
+                                game.get()
+                                    .map(|game| game.stats())
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|stats| {
+                                        view! {
+                                            <tr>
+                                                <td class:time-table-winner=stats.winner>{stats.name}</td>
+                                                <td>{stats.turns}</td>
+                                                <td>{config.format_time(stats.total)}</td>
+                                                <td>{config.format_time(stats.average)}</td>
+                                                <td>{config.format_time(stats.longest)}</td>
+                                                <td>{format!("{:.0}%", stats.handicap * 100.0)}</td>
+                                                <td>{config.format_time(stats.adjusted)}</td>
+                                                <td>{stats.score}</td>
+                                            </tr>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </tbody>
+                    </table>
+                    <div class="game-summary-duration">
+                        {move || {
+                            let (play, breaks) = game
+                                .with(|game| {
+                                    game.as_ref()
+                                        .map(|game| (game.play_time, game.break_time))
+                                        .unwrap_or_default()
+                                });
+                            config
+                                .with(|c| {
+                                    c.locale
+                                        .text("Played {play} · breaks {breaks} · total {total}")
+                                        .replace("{play}", &c.format_time(play))
+                                        .replace("{breaks}", &c.format_time(breaks))
+                                        .replace("{total}", &c.format_time(play + breaks))
+                                })
+                        }}
+                    </div>
+                    <div class="config-key-row game-summary-buttons">
+                        <button
+                            class="action-button"
+                            disabled=move || archived.get()
+                            on:click=move |_| save()
+                        >
+                            {move || if archived.get() { t("Archived") } else { t("Archive") }}
+                        </button>
+                        <button class="action-button" on:click=move |_| export()>
+                            {move || t("Export CSV")}
+                        </button>
+                        <button class="action-button" on:click=move |_| new_game()>
+                            {move || t("New game")}
+                        </button>
+                        <button class="action-button" on:click=move |_| show.set(false)>
+                            {move || t("Close")}
+                        </button>
+                    </div>
+                    <Show when=move || archive.with(|archive| !archive.games().is_empty())>
+                        <details class="game-log">
+                            <summary>{move || t("Past games")}</summary>
+                            <ul>
+                                {move || {
+                                    let config = config.get();
+                                    archive
+                                        .get()
+                                        .games()
+                                        .iter()
+                                        .rev()
+                                        .map(|game| {
+                                            let totals = game
+                                                .stats()
+                                                .into_iter()
+                                                .map(|stats| {
+                                                    format!(
+                                                        "{} {}",
+                                                        stats.name,
+                                                        config.format_time(stats.total),
+                                                    )
+                                                })
+                                                .collect::<Vec<_>>()
+                                                .join(", ");
+                                            view! {
+                                                <li>
+                                                    <span class="game-log-time">
+                                                        {game.date.get(..10).unwrap_or_default().to_string()}
+                                                    </span>
+                                                    {totals}
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view()
+                                }}
+                            </ul>
+                        </details>
+                    </Show>
+                </div>
+            </div>
+        </Show>
+    }
+}
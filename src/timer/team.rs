@@ -0,0 +1,99 @@
+use super::Config;
+use super::i18n::Locale;
+
+const TEAM_COLORS: [&str; 6] = [
+    "#e57373", "#64b5f6", "#81c784", "#ffd54f", "#ba68c8", "#4db6ac",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Team {
+    pub name: String,
+    pub color: String,
+    pub shared_bank: bool,
+}
+
+impl Team {
+    pub fn new(index: usize, locale: Locale) -> Self {
+        Self {
+            name: default_name(index, locale),
+            color: TEAM_COLORS[index % TEAM_COLORS.len()].to_string(),
+            shared_bank: false,
+        }
+    }
+}
+
+/// Name a new team starts out with.
+pub fn default_name(index: usize, locale: Locale) -> String {
+    locale
+        .text("Team {n}")
+        .replace("{n}", &(index + 1).to_string())
+}
+
+/// Order in which seats take their turns during one round.
+///
+/// Without teams (or with `alternate_teams` off) this is simply the seat order.
+/// Otherwise teams take turns one member at a time, e.g. A1 B1 A2 B2, and
+/// players without a team follow at the end of the round.
+pub fn turn_order(config: &Config) -> Vec<usize> {
+    if !config.alternate_teams || config.teams.is_empty() {
+        return (0..config.nplayers).collect();
+    }
+
+    let mut members = vec![Vec::new(); config.teams.len()];
+    let mut unassigned = Vec::new();
+    for seat in 0..config.nplayers {
+        match config.team_of(seat) {
+            Some(team) => members[team].push(seat),
+            None => unassigned.push(seat),
+        }
+    }
+
+    let longest = members.iter().map(Vec::len).max().unwrap_or(0);
+    let mut order = Vec::with_capacity(config.nplayers);
+    for k in 0..longest {
+        order.extend(members.iter().filter_map(|team| team.get(k)));
+    }
+    order.extend(unassigned);
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(player_teams: &[Option<usize>], teams: usize) -> Config {
+        let mut config = Config::for_test(player_teams.len());
+        config.player_teams = player_teams.to_vec();
+        config.teams = (0..teams)
+            .map(|index| Team::new(index, Locale::English))
+            .collect();
+        config.alternate_teams = true;
+        config
+    }
+
+    #[test]
+    fn seat_order_without_alternating_teams() {
+        let mut config = config(&[Some(1), Some(0), Some(1)], 2);
+        config.alternate_teams = false;
+        assert_eq!(turn_order(&config), vec![0, 1, 2]);
+        assert_eq!(turn_order(&self::config(&[None, None], 0)), vec![0, 1]);
+    }
+
+    #[test]
+    fn teams_alternate_and_unassigned_players_go_last() {
+        let config = config(&[Some(0), Some(0), None, Some(1), Some(1)], 2);
+        assert_eq!(turn_order(&config), vec![0, 3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn larger_teams_finish_the_round_alone() {
+        let config = config(&[Some(0), Some(1), Some(0), Some(0)], 2);
+        assert_eq!(turn_order(&config), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn players_of_removed_teams_count_as_unassigned() {
+        let config = config(&[Some(2), Some(0), Some(1)], 2);
+        assert_eq!(turn_order(&config), vec![1, 2, 0]);
+    }
+}
@@ -0,0 +1,148 @@
+const PLAYER_COLORS: [&str; 8] = [
+    "#4fc3f7", "#ff8a65", "#aed581", "#f06292", "#fff176", "#9575cd", "#4db6ac", "#e0e0e0",
+];
+
+/// Colors of a theme, applied to the page as CSS custom properties.
+pub struct Palette {
+    pub background: &'static str,
+    pub surface: &'static str,
+    pub card: &'static str,
+    pub button: &'static str,
+    /// Background of hovered or focused buttons and inputs.
+    pub hover: &'static str,
+    pub input: &'static str,
+    pub text: &'static str,
+    pub text_strong: &'static str,
+    pub border: &'static str,
+    pub outline: &'static str,
+    pub accent: &'static str,
+    /// Text that stands out, like the winner or an edited turn.
+    pub highlight: &'static str,
+    pub clock_active: &'static str,
+    pub clock_inactive: &'static str,
+    pub clock_text: &'static str,
+    pub divider: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl Theme {
+    pub const ALL: [(&'static str, &'static str); 3] = [
+        ("dark", "Dark"),
+        ("light", "Light"),
+        ("contrast", "High contrast"),
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::HighContrast => "contrast",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        match key {
+            "light" => Theme::Light,
+            "contrast" => Theme::HighContrast,
+            _ => Theme::Dark,
+        }
+    }
+
+    pub fn palette(&self) -> Palette {
+        match self {
+            Theme::Dark => Palette {
+                background: "#2f2f2f",
+                surface: "#1a1a1a",
+                card: "linear-gradient(135deg, #2a2a2a, #1e1e1e)",
+                button: "linear-gradient(135deg, #4a4a4a, #2a2a2a)",
+                hover: "linear-gradient(135deg, #5a5a5a, #3a3a3a)",
+                input: "linear-gradient(135deg, #3a3a3a, #2a2a2a)",
+                text: "#b0b0b0",
+                text_strong: "#e0e0e0",
+                border: "#444",
+                outline: "#1a1a1a",
+                accent: "#4caf50",
+                highlight: "#ffd54f",
+                clock_active: "radial-gradient(#8aca8d, #55a158, #1d421e)",
+                clock_inactive: "radial-gradient(#e28568, #ce4341, #862b0f)",
+                clock_text: "#1a1a1a",
+                divider: "gray",
+            },
+            Theme::Light => Palette {
+                background: "#f2f2f2",
+                surface: "#ffffff",
+                card: "linear-gradient(135deg, #ffffff, #ececec)",
+                button: "linear-gradient(135deg, #f5f5f5, #dcdcdc)",
+                hover: "linear-gradient(135deg, #e8e8e8, #cfcfcf)",
+                input: "linear-gradient(135deg, #ffffff, #f0f0f0)",
+                text: "#444",
+                text_strong: "#111",
+                border: "#bbb",
+                outline: "#888",
+                accent: "#2e7d32",
+                highlight: "#a15c00",
+                clock_active: "radial-gradient(#c8e6c9, #66bb6a, #2e7d32)",
+                clock_inactive: "radial-gradient(#ffcdd2, #e57373, #c62828)",
+                clock_text: "#111",
+                divider: "#ccc",
+            },
+            Theme::HighContrast => Palette {
+                background: "#000",
+                surface: "#000",
+                card: "#000",
+                button: "#000",
+                hover: "#333",
+                input: "#000",
+                text: "#fff",
+                text_strong: "#ffff00",
+                border: "#fff",
+                outline: "#fff",
+                accent: "#00ffff",
+                highlight: "#ffff00",
+                clock_active: "#00d000",
+                clock_inactive: "#d00000",
+                clock_text: "#000",
+                divider: "#fff",
+            },
+        }
+    }
+}
+
+/// Default color for the player in `seat`.
+pub fn player_color(seat: usize) -> String {
+    PLAYER_COLORS[seat % PLAYER_COLORS.len()].to_string()
+}
+
+/// CSS custom properties for `theme`, with fonts scaled to `font_scale` percent.
+pub fn css_variables(theme: Theme, font_scale: u32) -> String {
+    let p = theme.palette();
+    [
+        ("--bg", p.background),
+        ("--surface", p.surface),
+        ("--card", p.card),
+        ("--button", p.button),
+        ("--hover", p.hover),
+        ("--input", p.input),
+        ("--text", p.text),
+        ("--text-strong", p.text_strong),
+        ("--border", p.border),
+        ("--outline", p.outline),
+        ("--accent", p.accent),
+        ("--highlight", p.highlight),
+        ("--clock-active", p.clock_active),
+        ("--clock-inactive", p.clock_inactive),
+        ("--clock-text", p.clock_text),
+        ("--divider", p.divider),
+    ]
+    .iter()
+    .map(|(name, value)| format!("{}: {};", name, value))
+    .chain([format!("--font-scale: {};", font_scale as f32 / 100.0)])
+    .collect::<Vec<_>>()
+    .join(" ")
+}
@@ -0,0 +1,81 @@
+use super::native;
+use leptos::{logging, prelude::*};
+use std::cell::{Cell, RefCell};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use web_sys::js_sys::{Function, Promise, Reflect};
+
+thread_local! {
+    static WANTED: Cell<bool> = const { Cell::new(false) };
+    static SENTINEL: RefCell<Option<JsValue>> = const { RefCell::new(None) };
+}
+
+/// Keeps the screen on, through the desktop app's power management when available and the
+/// Screen Wake Lock API otherwise.
+pub fn acquire() {
+    WANTED.set(true);
+    if native::invoke("inhibit_sleep", &[("inhibit", true.into())]) {
+        return;
+    }
+    if SENTINEL.with_borrow(Option::is_some) {
+        return;
+    }
+    if let Err(err) = request_screen_lock() {
+        logging::warn!("wake lock unavailable: {:?}", err);
+    }
+}
+
+/// Lets the screen turn off again.
+pub fn release() {
+    WANTED.set(false);
+    if native::invoke("inhibit_sleep", &[("inhibit", false.into())]) {
+        return;
+    }
+    if let Some(sentinel) = SENTINEL.take() {
+        release_sentinel(&sentinel);
+    }
+}
+
+/// The browser drops its lock whenever the page is hidden, so it is requested again once
+/// the page becomes visible while still wanted.
+pub fn reacquire_if_visible() {
+    let visible = document().visibility_state() == web_sys::VisibilityState::Visible;
+    if WANTED.get() && visible && !native::is_desktop() {
+        SENTINEL.take();
+        if let Err(err) = request_screen_lock() {
+            logging::warn!("wake lock unavailable: {:?}", err);
+        }
+    }
+}
+
+fn request_screen_lock() -> Result<(), JsValue> {
+    let wake_lock = Reflect::get(&window().navigator(), &"wakeLock".into())?;
+    if wake_lock.is_undefined() {
+        return Err("navigator.wakeLock is not supported".into());
+    }
+    let request: Function = Reflect::get(&wake_lock, &"request".into())?.dyn_into()?;
+    let promise: Promise = request.call1(&wake_lock, &"screen".into())?.dyn_into()?;
+
+    let on_lock = Closure::once_into_js(move |sentinel: JsValue| {
+        // The game may have been paused while the request was pending.
+        if WANTED.get() {
+            SENTINEL.set(Some(sentinel));
+        } else {
+            release_sentinel(&sentinel);
+        }
+    });
+    let on_error = Closure::once_into_js(move |err: JsValue| {
+        logging::warn!("wake lock request failed: {:?}", err);
+    });
+    // One-shot callbacks free themselves once the promise settles.
+    let then: Function = Reflect::get(&promise, &"then".into())?.dyn_into()?;
+    then.call2(&promise, &on_lock, &on_error)?;
+    Ok(())
+}
+
+fn release_sentinel(sentinel: &JsValue) {
+    if let Ok(release) =
+        Reflect::get(sentinel, &"release".into()).and_then(|release| release.dyn_into::<Function>())
+    {
+        let _ = release.call0(sentinel);
+    }
+}